@@ -15,6 +15,12 @@ fn main() {
         .add_plugins(WorldInspectorPlugin::default())
         .add_plugins(camera_plugin::CameraPlugin)
         .add_plugins(ClothPlugin)
+        // Grab the cloth with the middle mouse button
+        .add_plugins(ClothGrabPlugin)
+        .insert_resource(ClothGrabConfig {
+            button: MouseButton::Middle,
+            compliance: Some(1e-4),
+        })
         .add_systems(Startup, (spawn_cloth, setup))
         .run();
 }
@@ -35,7 +41,8 @@ fn setup(
         (Color::YELLOW, [0.0, -10.0]),
         (Color::RED, [0.0, 10.0]),
     ]
-    .map(|(color, [x, z])| {
+    .into_iter()
+    .for_each(|(color, [x, z])| {
         commands.spawn(PbrBundle {
             mesh: mesh_handle.clone(),
             transform: Transform::from_xyz(x, 1.0, z),
@@ -96,6 +103,8 @@ fn spawn_cloth(
             VertexAnchor {
                 custom_target: Some(entity_b),
                 custom_offset: Some(-Vec3::X * 30.0),
+                // Soft anchor, lagging behind the target
+                compliance: Some(5e-4),
                 ..Default::default()
             },
         );
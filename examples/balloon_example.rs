@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::{ResourceInspectorPlugin, WorldInspectorPlugin};
-use bevy_silk::prelude::*;
+use bevy_silk::{components::cloth::Cloth, prelude::*};
 
 mod camera_plugin;
 
@@ -20,6 +20,7 @@ fn main() {
             ..Default::default()
         })
         .add_systems(Startup, (spawn_cloth, setup))
+        .add_systems(Update, inflate)
         .run();
 }
 
@@ -36,7 +37,8 @@ fn setup(
         (Color::YELLOW, [0.0, -10.0]),
         (Color::RED, [0.0, 10.0]),
     ]
-    .map(|(color, [x, z])| {
+    .into_iter()
+    .for_each(|(color, [x, z])| {
         commands.spawn(PbrBundle {
             mesh: mesh_handle.clone(),
             transform: Transform::from_xyz(x, 0.0, z),
@@ -69,7 +71,24 @@ fn spawn_cloth(
             transform: Transform::from_xyz(0.0, 2.0, 0.0),
             ..Default::default()
         },
-        ClothBuilder::new().with_pinned_vertex_ids(0..=0),
+        ClothBuilder::new()
+            .with_pinned_vertex_ids(0..=0)
+            .with_volume_preservation(1.0, 0.5),
         Name::new("Balloon"),
     ));
 }
+
+fn inflate(mut cloth_query: Query<&mut Cloth>, keys: Res<Input<KeyCode>>, time: Res<Time>) {
+    let delta = if keys.pressed(KeyCode::Up) {
+        time.delta_seconds()
+    } else if keys.pressed(KeyCode::Down) {
+        -time.delta_seconds()
+    } else {
+        return;
+    };
+    for mut cloth in &mut cloth_query {
+        if let Some(ratio) = cloth.volume_constraint.as_ref().map(|c| c.target_ratio) {
+            cloth.set_volume_ratio((ratio + delta).max(0.0));
+        }
+    }
+}
@@ -50,7 +50,8 @@ fn setup(
         (Color::YELLOW, [0.0, -10.0]),
         (Color::RED, [0.0, 10.0]),
     ]
-    .map(|(color, [x, z])| {
+    .into_iter()
+    .for_each(|(color, [x, z])| {
         commands.spawn(PbrBundle {
             mesh: mesh_handle.clone(),
             transform: Transform::from_xyz(x, 0.0, z),
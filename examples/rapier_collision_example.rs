@@ -54,7 +54,8 @@ fn setup(
         (Color::YELLOW, [0.0, -10.0]),
         (Color::RED, [0.0, 10.0]),
     ]
-    .map(|(color, [x, z])| {
+    .into_iter()
+    .for_each(|(color, [x, z])| {
         commands.spawn((
             PbrBundle {
                 mesh: mesh_handle.clone(),
@@ -107,6 +108,7 @@ fn spawn_cloth(
         cloth,
         ClothCollider {
             dampen_others: Some(0.02),
+            continuous: true,
             ..Default::default()
         },
         Name::new("Cloth"),
@@ -0,0 +1,71 @@
+use bevy::math::Vec3A;
+
+/// Computes the aerodynamic force applied on a cloth triangle by the air
+/// flowing at `wind_velocity`. The force is made of:
+/// - a drag force, along the relative air velocity
+/// - a lift force, perpendicular to the relative air velocity
+///
+/// Both forces scale with the triangle area facing the relative air velocity,
+/// a triangle edge-on to the wind receiving no force.
+///
+/// # Arguments
+///
+/// * `points` - the triangle point positions
+/// * `velocity` - the triangle velocity
+/// * `wind_velocity` - the air velocity
+/// * `drag_coefficient` - the drag coefficient
+/// * `lift_coefficient` - the lift coefficient
+#[must_use]
+pub fn triangle_force(
+    [a, b, c]: [Vec3A; 3],
+    velocity: Vec3A,
+    wind_velocity: Vec3A,
+    drag_coefficient: f32,
+    lift_coefficient: f32,
+) -> Vec3A {
+    let relative_velocity = wind_velocity - velocity;
+    let speed = relative_velocity.length();
+    let cross = (b - a).cross(c - a);
+    let double_area = cross.length();
+    if speed <= f32::EPSILON || double_area <= f32::EPSILON {
+        return Vec3A::ZERO;
+    }
+    let normal = cross / double_area;
+    let area = double_area / 2.0;
+    let normal_velocity = relative_velocity.dot(normal);
+    let drag = relative_velocity * (drag_coefficient * normal_velocity.abs());
+    // Normal component perpendicular to the air flow
+    let lift_direction = normal - relative_velocity * (normal_velocity / (speed * speed));
+    let lift = lift_direction * (lift_coefficient * normal_velocity * speed);
+    (drag + lift) * (area / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIANGLE: [Vec3A; 3] = [Vec3A::ZERO, Vec3A::X, Vec3A::Y];
+
+    #[test]
+    fn edge_on_triangle_has_no_force() {
+        let force = triangle_force(TRIANGLE, Vec3A::ZERO, Vec3A::X * 10.0, 1.0, 1.0);
+        assert!(force.length() < 1e-6);
+    }
+
+    #[test]
+    fn facing_triangle_is_pushed() {
+        let force = triangle_force(TRIANGLE, Vec3A::ZERO, Vec3A::NEG_Z * 10.0, 1.0, 1.0);
+        assert!(force.z < 0.0);
+        assert!(force.x.abs() < 1e-6 && force.y.abs() < 1e-6);
+        let oblique = triangle_force(
+            TRIANGLE,
+            Vec3A::ZERO,
+            Vec3A::new(10.0, 0.0, -10.0),
+            0.0,
+            1.0,
+        );
+        // Lift is perpendicular to the air flow
+        assert!(oblique.dot(Vec3A::new(1.0, 0.0, -1.0)).abs() < 1e-4);
+        assert!(oblique.length() > 0.0);
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::{log, math::Vec3, reflect::Reflect, utils::HashMap};
+use std::f32::consts::PI;
+
+/// Defines the rest dihedral angle of cloth bending constraints, which is the
+/// angle between the normals of two adjacent triangles.
+///
+/// A `0.0` angle means the two triangles are coplanar
+#[derive(Debug, Copy, Clone, Default, Reflect)]
+pub enum BendingAngle {
+    #[default]
+    /// The rest angle will be the actual angle between the triangles
+    Auto,
+    /// Custom rest angle in radians
+    Fixed(f32),
+}
+
+/// Bending constraint between two adjacent triangles sharing an edge
+#[derive(Debug, Copy, Clone, Reflect)]
+pub struct BendingConstraint {
+    /// Constraint point ids:
+    /// - 0 and 1: The shared edge points
+    /// - 2 and 3: The opposite point of each triangle
+    pub points: [usize; 4],
+    /// Rest dihedral angle in radians (See [`BendingAngle`])
+    pub rest_angle: f32,
+}
+
+impl BendingAngle {
+    /// Retrieves the rest angle from the 4 points of a bending constraint
+    #[must_use]
+    pub fn get_angle(&self, points: [Vec3; 4]) -> f32 {
+        match self {
+            Self::Auto => BendingConstraint::dihedral_angle(points),
+            Self::Fixed(v) => *v,
+        }
+    }
+}
+
+impl BendingConstraint {
+    /// Computes the dihedral angle between the two triangles, `0.0` meaning
+    /// the triangles are coplanar
+    #[must_use]
+    pub fn dihedral_angle([p1, p2, p3, p4]: [Vec3; 4]) -> f32 {
+        let [p2, p3, p4] = [p2 - p1, p3 - p1, p4 - p1];
+        let n1 = p2.cross(p3).normalize_or_zero();
+        let n2 = p2.cross(p4).normalize_or_zero();
+        PI - n1.dot(n2).clamp(-1.0, 1.0).acos()
+    }
+
+    /// Generates bending constraints from mesh triangle `indices`, for every
+    /// edge shared by exactly two triangles
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - the cloth point positions
+    /// * `indices` - the mesh indices
+    /// * `angle` - the rest angle option
+    #[must_use]
+    pub fn generate(positions: &[Vec3], indices: &[usize], angle: BendingAngle) -> Vec<Self> {
+        let mut edges: HashMap<[usize; 2], Vec<usize>> = HashMap::with_capacity(indices.len());
+        for truple in indices.chunks_exact(3) {
+            let [a, b, c] = [truple[0], truple[1], truple[2]];
+            for (edge, opposite) in [([a, b], c), ([b, c], a), ([c, a], b)] {
+                let key = if edge[0] < edge[1] {
+                    edge
+                } else {
+                    [edge[1], edge[0]]
+                };
+                edges.entry(key).or_default().push(opposite);
+            }
+        }
+        let mut constraints: Vec<_> = edges
+            .into_iter()
+            .filter_map(|([a, b], opposites)| match opposites[..] {
+                [c, d] => {
+                    let points = [a, b, c, d];
+                    Some(Self {
+                        points,
+                        rest_angle: angle.get_angle(points.map(|i| positions[i])),
+                    })
+                }
+                [_] => None,
+                _ => {
+                    log::warn!(
+                        "Cloth edge between points {a} and {b} is shared by {} triangles, skipping \
+                         its bending constraint",
+                        opposites.len()
+                    );
+                    None
+                }
+            })
+            .collect();
+        constraints.sort_unstable_by_key(|c| c.points);
+        constraints
+    }
+
+    /// Computes the position corrections of the constraint points, following
+    /// the dihedral bending constraint of *Position Based Dynamics* (Müller et
+    /// al. 2007)
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - the current constraint point positions
+    /// * `weights` - the constraint point weights (inverse masses)
+    ///
+    /// Returns `None` if the constraint is degenerate or already satisfied
+    #[must_use]
+    pub fn corrections(&self, [p1, p2, p3, p4]: [Vec3; 4], weights: [f32; 4]) -> Option<[Vec3; 4]> {
+        let [p2, p3, p4] = [p2 - p1, p3 - p1, p4 - p1];
+        let (cross_23, cross_24) = (p2.cross(p3), p2.cross(p4));
+        let (len_23, len_24) = (cross_23.length(), cross_24.length());
+        if len_23 <= f32::EPSILON || len_24 <= f32::EPSILON {
+            return None;
+        }
+        let (n1, n2) = (cross_23 / len_23, cross_24 / len_24);
+        let d = n1.dot(n2).clamp(-1.0, 1.0);
+        let constraint = d.acos() - (PI - self.rest_angle);
+        if constraint.abs() <= f32::EPSILON {
+            return None;
+        }
+        let q3 = (p2.cross(n2) + n1.cross(p2) * d) / len_23;
+        let q4 = (p2.cross(n1) + n2.cross(p2) * d) / len_24;
+        let q2 = -(p3.cross(n2) + n1.cross(p3) * d) / len_23
+            - (p4.cross(n1) + n2.cross(p4) * d) / len_24;
+        let q1 = -q2 - q3 - q4;
+        let gradients = [q1, q2, q3, q4];
+        let sum: f32 = gradients
+            .iter()
+            .zip(weights)
+            .map(|(q, w)| w * q.length_squared())
+            .sum();
+        if sum <= f32::EPSILON {
+            return None;
+        }
+        let scale = -(1.0 - d * d).sqrt() * constraint / sum;
+        Some(std::array::from_fn(|i| gradients[i] * weights[i] * scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAT: [Vec3; 4] = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::NEG_Y];
+
+    #[test]
+    fn flat_angle() {
+        assert!(BendingConstraint::dihedral_angle(FLAT).abs() < 1e-5);
+    }
+
+    #[test]
+    fn generates_shared_edges() {
+        let positions = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::X + Vec3::Y];
+        let constraints =
+            BendingConstraint::generate(&positions, &[0, 1, 2, 2, 1, 3], BendingAngle::Auto);
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0].points[..2], [1, 2]);
+    }
+
+    #[test]
+    fn corrections_reduce_fold() {
+        let constraint = BendingConstraint {
+            points: [0, 1, 2, 3],
+            rest_angle: 0.0,
+        };
+        let mut points = FLAT;
+        points[3].z = 0.5;
+        let before = BendingConstraint::dihedral_angle(points);
+        let corrections = constraint
+            .corrections(points, [0.0, 0.0, 1.0, 1.0])
+            .unwrap();
+        for (p, c) in points.iter_mut().zip(corrections) {
+            *p += c;
+        }
+        assert!(BendingConstraint::dihedral_angle(points) < before);
+    }
+}
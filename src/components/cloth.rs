@@ -1,28 +1,73 @@
 use crate::{
-    stick::{StickGeneration, StickLen, StickMode},
-    vertex_anchor::VertexAnchor,
+    aerodynamics,
+    bending::{BendingAngle, BendingConstraint},
+    query::{self, ClothPointProjection, ClothRayHit},
+    self_collision::SpatialHash,
+    stick::{StickCategory, StickGeneration, StickLen, StickMode, Sticks},
+    tear::{ClothTear, PointSplit},
+    tether::Tether,
+    vertex_anchor::{AnchorChange, VertexAnchor},
+    volume::VolumeConstraint,
 };
 use bevy::{
     ecs::prelude::Component,
     log,
-    math::{Mat4, Vec3},
+    math::{Mat4, Ray, Vec3, Vec3A},
     prelude::{Entity, GlobalTransform},
-    utils::HashMap,
+    tasks::{ParallelSliceMut, TaskPool},
+    utils::{HashMap, HashSet},
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
 };
 
 /// A stick is defined by the two ids of the connectecte points
 pub type StickId = [usize; 2];
 
-macro_rules! get_point {
-    ($id:expr, $points:expr, $anchored_points:expr) => {
-        match $points.get($id) {
-            None => {
-                log::warn!("Failed to retrieve a Cloth point at index {}", $id);
-                continue;
-            }
-            Some(p) => (*p, $anchored_points.contains_key(&$id)),
+/// Lagrange multiplier delta and point position corrections of a stick solved
+/// by the XPBD solver
+type XpbdCorrection = (f32, [Vec3A; 2]);
+
+/// Amount of sticks of a batch solved by a single task, smaller batches are
+/// solved on the current thread
+const STICK_CHUNK_SIZE: usize = 256;
+
+/// Fills the `corrections` of the given stick indexes with `compute`, in
+/// parallel on `task_pool` if there are more than [`STICK_CHUNK_SIZE`] sticks
+fn compute_stick_corrections<T: Send>(
+    task_pool: &TaskPool,
+    mut corrections: &mut [(usize, Option<T>)],
+    compute: impl Fn(usize) -> Option<T> + Sync,
+) {
+    if corrections.len() <= STICK_CHUNK_SIZE {
+        for (index, correction) in corrections.iter_mut() {
+            *correction = compute(*index);
         }
-    };
+        return;
+    }
+    corrections.par_chunk_map_mut(task_pool, STICK_CHUNK_SIZE, |chunk| {
+        for (index, correction) in chunk {
+            *correction = compute(*index);
+        }
+    });
+}
+
+/// Returns `true` if `point` is rigidly anchored in `anchored_points`
+fn is_pinned(anchored_points: &HashMap<usize, (VertexAnchor, Vec3)>, point: usize) -> bool {
+    anchored_points
+        .get(&point)
+        .is_some_and(|(anchor, _)| anchor.is_rigid())
+}
+
+/// Returns `true` if `point` is not rigidly anchored, only looking up the
+/// anchors of points with a null effective inverse mass in `point_weights`
+fn is_free(
+    point_weights: &[f32],
+    anchored_points: &HashMap<usize, (VertexAnchor, Vec3)>,
+    point: usize,
+) -> bool {
+    point_weights[point] > 0.0 || !is_pinned(anchored_points, point)
 }
 
 /// Cloth component. Do not insert it directly, use [`ClothBuilder`] instead.
@@ -31,27 +76,125 @@ macro_rules! get_point {
 #[derive(Debug, Clone, Component, Default)]
 #[must_use]
 pub struct Cloth {
-    /// cloth points unaffected by physics and following an anchor
+    /// cloth points following an anchor, unaffected by physics unless the
+    /// anchor is soft (See [`VertexAnchor::compliance`])
     /// The key is the point index and the value is a tuple with:
     /// - 0: The [`VertexAnchor`] anchor
-    /// - 1: The initial local space vertex position
+    /// - 1: The initial local space vertex position, relative to the anchor
+    ///   target
+    ///
+    /// Note: use [`Self::pin_point`] and [`Self::unpin_point`] to edit the
+    /// anchors at runtime
     pub anchored_points: HashMap<usize, (VertexAnchor, Vec3)>,
     /// Current Cloth points 3D positions in world space
-    pub current_point_positions: Vec<Vec3>,
+    pub current_point_positions: Vec<Vec3A>,
     /// Old Cloth points 3D positions in world space
-    pub previous_point_positions: Vec<Vec3>,
-    /// Cloth sticks lengths
+    pub previous_point_positions: Vec<Vec3A>,
+    /// Cloth points 3D positions in world space at the beginning of the last
+    /// fixed step, used to interpolate the rendered positions
+    pub step_start_point_positions: Vec<Vec3A>,
+    /// Cloth points 3D positions in world space at the beginning of the
+    /// current frame, used to sweep the point motions of the whole frame
+    /// (See [`Self::solve_swept_collisions`])
+    pub frame_start_point_positions: Vec<Vec3A>,
+    /// Cloth points inverse masses, weighting the constraint corrections.
+    /// Heavier points (lower inverse mass) move less.
     ///
-    /// * key: array of the two connected points indexes
-    /// * value: the target distance between the points
+    /// Note: anchored points are infinitely heavy regardless of their mass.
+    /// Use [`Self::set_point_mass`] to edit them, keeping the effective
+    /// inverse masses used by the solvers in sync
+    pub inverse_masses: Vec<f32>,
+    /// Effective inverse mass of every point used by the solvers, `0.0` for
+    /// rigidly anchored points (See [`Self::inverse_mass`])
+    point_weights: Vec<f32>,
+    /// Elapsed time in seconds not yet simulated, used by fixed time steps
+    pub time_accumulator: f32,
+    /// Amount of fixed steps simulated since the cloth creation
+    pub step_count: u64,
+    /// Amount of consecutive steps at rest, see [`Self::update_sleep`]
+    rest_steps: u16,
+    /// Set when the cloth is at rest and no longer simulated
+    sleeping: bool,
+    /// Wind velocity of the last step, waking up the cloth when it changes
+    wind_velocity: Vec3,
+    /// Cloth sticks, see [`Self::sticks`]
+    sticks: Sticks,
+    /// Independent stick index batches, see [`Self::stick_batches`]
+    stick_batches: Vec<Vec<usize>>,
+    /// Set when the sticks changed and the batches must be regenerated
+    stick_batches_outdated: bool,
+    /// Stick position corrections of the current batch, kept between steps
+    stick_corrections: Vec<(usize, Option<[Vec3A; 2]>)>,
+    /// Stick Lagrange multiplier and position corrections of the current
+    /// batch, used by the XPBD solver and kept between steps
+    xpbd_corrections: Vec<(usize, Option<XpbdCorrection>)>,
+    /// Aerodynamic force of every point, kept between steps
+    aerodynamic_forces: Vec<Vec3A>,
+    /// Accumulated position corrections and correction count of every point,
+    /// used by the Jacobi iteration and kept between steps
+    point_corrections: Vec<(Vec3A, f32)>,
+    /// Sticks pull of every breakable anchored point, kept between steps
+    anchor_pulls: HashMap<usize, Vec3A>,
+    /// Anchored points broken during the current step, kept between steps
+    broken_anchors: Vec<usize>,
+    /// Sticks torn during the current step, kept between steps
+    torn_sticks: Vec<StickId>,
+    /// Old and new ids of the sticks moved to duplicated points by the last
+    /// tear, kept between steps
+    renamed_sticks: Vec<(StickId, StickId)>,
+    /// Anchors pinned (`Some`) or unpinned (`None`) at runtime, kept when the
+    /// cloth is rebuilt
+    anchor_edits: HashMap<usize, Option<(VertexAnchor, Vec3)>>,
+    /// Point masses set at runtime, kept when the cloth is rebuilt
+    mass_edits: HashMap<usize, f32>,
+    /// Cloth triangles, as three point indexes
     ///
     /// Note: this field will be automatically populated from mesh data
-    pub stick_lengths: HashMap<StickId, f32>,
-    /// Cloth sticks behaviour modes
+    pub triangles: Vec<[usize; 3]>,
+    /// Torn cloth edges, as two ordered point indexes
+    pub torn_edges: HashSet<[usize; 2]>,
+    /// Cloth sticks Lagrange multipliers, used by the XPBD solver and reset
+    /// every step
+    stick_lambdas: Vec<f32>,
+    /// Cloth bending constraints between adjacent triangles
+    ///
+    /// Note: this field will be automatically populated from mesh data if the
+    /// bending stiffness is set
+    pub bending_constraints: Vec<BendingConstraint>,
+    /// Cloth bending stiffness, between 0.0 (no bending resistance) and 1.0
+    pub bending_stiffness: f32,
+    /// Cloth long range attachment constraints, between free points and their
+    /// nearest anchored point
     ///
-    /// * key: array of the two connected points indexes
-    /// * value: the stick mode
-    pub stick_modes: HashMap<StickId, StickMode>,
+    /// Note: this field will be automatically populated if
+    /// [`Self::tether_stretch`] is set, and updated when the anchored points
+    /// change
+    pub tethers: Vec<Tether>,
+    /// Maximum stretch ratio of the tethers, the points can't go further
+    /// than their rest geodesic distance to their anchor multiplied by this
+    /// value. If not set, no tethers are applied
+    pub tether_stretch: Option<f32>,
+    /// Optional volume preservation constraint, for closed cloth meshes
+    pub volume_constraint: Option<VolumeConstraint>,
+    /// Optional self collision thickness, the minimum distance between two
+    /// cloth points not connected by a stick. If not set, the cloth can pass
+    /// through itself
+    pub self_collision_thickness: Option<f32>,
+    /// Spatial hash of the cloth points, rebuilt every self collision step
+    spatial_hash: SpatialHash,
+    /// Anchored point ids used to generate the current tethers
+    tethered_anchors: Vec<usize>,
+    /// Tears not yet applied to the cloth rendering
+    pending_tears: Vec<ClothTear>,
+    /// World space anchor positions of the anchored points, waking up the
+    /// cloth when they move
+    anchor_targets: HashMap<usize, Vec3A>,
+    /// Anchor changes not yet sent as events
+    pending_anchor_changes: Vec<AnchorChange>,
+    /// Broken anchors not yet sent as events
+    pending_anchor_breaks: Vec<(usize, VertexAnchor)>,
+    /// Set when the cloth fell asleep, until sent as an event
+    pending_settled: bool,
 }
 
 impl Cloth {
@@ -71,7 +214,299 @@ impl Cloth {
         self.current_point_positions
             .iter()
             // ..computed to local space
-            .map(move |p| matrix.transform_point3(*p))
+            .map(move |p| matrix.transform_point3a(*p).into())
+    }
+
+    /// Computes the new local vertex positions of the cloth mesh, interpolated
+    /// between the beginning and the end of the last fixed step
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - the `GlobalTransform` associated to the cloth entity
+    /// * `alpha` - the interpolation factor between `0.0` (beginning of the
+    ///   last step) and `1.0` (current state)
+    #[must_use]
+    pub fn compute_interpolated_vertex_positions(
+        &self,
+        transform: &GlobalTransform,
+        alpha: f32,
+    ) -> impl ExactSizeIterator<Item = Vec3> + '_ {
+        let matrix = transform.compute_matrix().inverse();
+
+        // World space positions..
+        self.step_start_point_positions
+            .iter()
+            .zip(&self.current_point_positions)
+            // ..interpolated..
+            .map(move |(start, end)| start.lerp(*end, alpha))
+            // ..computed to local space
+            .map(move |p| matrix.transform_point3a(p).into())
+    }
+
+    /// Computes the strain of every cloth point, as the average stretch ratio
+    /// of its adjacent sticks: `0.0` at rest, positive when stretched and
+    /// negative when compressed
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compute_point_strains(&self) -> Vec<f32> {
+        let mut strains = vec![(0.0, 0_u32); self.current_point_positions.len()];
+        for ([a, b], rest_length) in self.sticks.ids().iter().zip(self.sticks.lengths()) {
+            let length =
+                self.current_point_positions[*a].distance(self.current_point_positions[*b]);
+            let strain = length / rest_length.max(f32::EPSILON) - 1.0;
+            for point in [a, b] {
+                strains[*point].0 += strain;
+                strains[*point].1 += 1;
+            }
+        }
+        strains
+            .into_iter()
+            .map(|(sum, count)| if count == 0 { 0.0 } else { sum / count as f32 })
+            .collect()
+    }
+
+    /// Computes the local space velocity of every cloth point over the last
+    /// simulation step
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - the `GlobalTransform` associated to the cloth entity
+    /// * `delta_time` - the duration of the last simulation step
+    #[must_use]
+    pub fn compute_vertex_velocities(
+        &self,
+        transform: &GlobalTransform,
+        delta_time: f32,
+    ) -> impl ExactSizeIterator<Item = Vec3> + '_ {
+        let matrix = transform.compute_matrix().inverse();
+        let inverse_delta = delta_time.max(f32::EPSILON).recip();
+
+        // World space displacements..
+        self.current_point_positions
+            .iter()
+            .zip(&self.previous_point_positions)
+            // ..computed to local space velocities
+            .map(move |(current, previous)| {
+                Vec3::from(matrix.transform_vector3a(*current - *previous) * inverse_delta)
+            })
+    }
+
+    /// Copies the simulated state of `other`, if both cloths share the same
+    /// topology: the point positions, and the anchors and masses edited at
+    /// runtime (See [`Self::pin_point`], [`Self::unpin_point`] and
+    /// [`Self::set_point_mass`]). Used to keep the cloth state when rebuilding
+    /// it.
+    ///
+    /// Returns `true` if the state was copied
+    pub fn copy_state_from(&mut self, other: &Self) -> bool {
+        if self.current_point_positions.len() != other.current_point_positions.len()
+            || self.triangles != other.triangles
+        {
+            return false;
+        }
+        self.current_point_positions
+            .clone_from(&other.current_point_positions);
+        self.previous_point_positions
+            .clone_from(&other.previous_point_positions);
+        self.step_start_point_positions
+            .clone_from(&other.step_start_point_positions);
+        self.frame_start_point_positions
+            .clone_from(&other.frame_start_point_positions);
+        for (point, edit) in &other.anchor_edits {
+            if let Some(anchor) = edit {
+                self.anchored_points.insert(*point, *anchor);
+            } else {
+                self.anchored_points.remove(point);
+            }
+        }
+        self.anchor_edits.clone_from(&other.anchor_edits);
+        for (point, mass) in &other.mass_edits {
+            self.set_point_mass(*point, *mass);
+        }
+        self.update_point_weights();
+        true
+    }
+
+    /// Stores the current point positions as the beginning of a new fixed
+    /// step, used to interpolate the rendered positions, and increments
+    /// [`Self::step_count`]
+    pub fn begin_fixed_step(&mut self) {
+        if !self.sleeping {
+            self.step_start_point_positions
+                .clone_from(&self.current_point_positions);
+        }
+        self.step_count += 1;
+    }
+
+    /// Stores the current point positions as the beginning of a new frame,
+    /// swept by [`Self::solve_swept_collisions`] once every step and substep
+    /// of the frame is simulated
+    pub fn begin_frame(&mut self) {
+        self.frame_start_point_positions
+            .clone_from(&self.current_point_positions);
+    }
+
+    /// Computes a hash of the cloth simulation state: the step count, the
+    /// point positions and the sticks. Two cloths with the same state hash
+    /// are bit-identical, allowing to check the simulation determinism (See
+    /// [`ClothConfig::deterministic`]).
+    ///
+    /// Note: The hash is stable across runs of the same build but not across
+    /// Rust versions
+    ///
+    /// [`ClothConfig::deterministic`]: crate::config::ClothConfig::deterministic
+    #[must_use]
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.step_count.hash(&mut hasher);
+        for position in self
+            .current_point_positions
+            .iter()
+            .chain(&self.previous_point_positions)
+        {
+            position.to_array().map(f32::to_bits).hash(&mut hasher);
+        }
+        self.sticks.ids().hash(&mut hasher);
+        for length in self.sticks.lengths() {
+            length.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Retrieves the world space positions of the given `triangle` points
+    fn triangle_positions(&self, triangle: [usize; 3]) -> [Vec3A; 3] {
+        triangle.map(|i| self.current_point_positions[i])
+    }
+
+    /// Casts a world space `ray` against the simulated cloth triangles,
+    /// returning the nearest hit if any
+    #[must_use]
+    pub fn cast_ray(&self, ray: Ray) -> Option<ClothRayHit> {
+        let (triangle, (distance, barycentric)) = self
+            .triangles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, triangle)| {
+                query::ray_triangle_intersection(ray, self.triangle_positions(*triangle))
+                    .map(|hit| (i, hit))
+            })
+            .min_by(|(_, (a, _)), (_, (b, _))| a.total_cmp(b))?;
+        let [a, b, c] = self.triangle_positions(self.triangles[triangle]);
+        let normal = Vec3::from((b - a).cross(c - a).normalize_or_zero());
+        Some(ClothRayHit {
+            triangle,
+            distance,
+            barycentric,
+            point: ray.get_point(distance),
+            normal: if normal.dot(ray.direction) > 0.0 {
+                -normal
+            } else {
+                normal
+            },
+        })
+    }
+
+    /// Retrieves the closest point of the simulated cloth triangles to the
+    /// given world space `point`, if the cloth has triangles
+    #[must_use]
+    pub fn closest_point(&self, point: Vec3) -> Option<ClothPointProjection> {
+        let point = Vec3A::from(point);
+        self.triangles
+            .iter()
+            .enumerate()
+            .map(|(triangle, ids)| {
+                let [a, b, c] = self.triangle_positions(*ids);
+                let barycentric = query::closest_point_on_triangle(point, [a, b, c]);
+                let closest = a * barycentric.x + b * barycentric.y + c * barycentric.z;
+                ClothPointProjection {
+                    triangle,
+                    distance: closest.distance(point),
+                    barycentric,
+                    point: closest.into(),
+                }
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Returns `true` if the cloth is at rest and no longer simulated, see
+    /// [`ClothConfig::sleep_threshold`]
+    ///
+    /// [`ClothConfig::sleep_threshold`]: crate::config::ClothConfig::sleep_threshold
+    #[inline]
+    #[must_use]
+    pub const fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Wakes up the cloth, resuming its simulation
+    pub fn wake_up(&mut self) {
+        self.sleeping = false;
+        self.rest_steps = 0;
+    }
+
+    /// Updates the sleep state of the cloth according to the point
+    /// displacements of the last step. The cloth falls asleep after `steps`
+    /// consecutive steps without any point moving further than `threshold`.
+    ///
+    /// Should be called once per step: with substeps, after the last substep
+    /// of every fixed step, with a `threshold` divided by the substep count
+    /// as only the displacement of the last substep is measured.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The maximum point displacement of a (sub)step at rest
+    /// * `steps` - The amount of consecutive steps at rest before sleeping
+    pub fn update_sleep(&mut self, threshold: f32, steps: u16) {
+        let threshold_squared = threshold * threshold;
+        let at_rest = self
+            .current_point_positions
+            .iter()
+            .zip(&self.previous_point_positions)
+            .all(|(current, previous)| current.distance_squared(*previous) <= threshold_squared);
+        if !at_rest {
+            self.rest_steps = 0;
+            return;
+        }
+        self.rest_steps = self.rest_steps.saturating_add(1);
+        if self.rest_steps >= steps {
+            // The remaining velocity is discarded
+            self.previous_point_positions
+                .clone_from(&self.current_point_positions);
+            self.step_start_point_positions
+                .clone_from(&self.current_point_positions);
+            self.sleeping = true;
+            self.pending_settled = true;
+        }
+    }
+
+    /// Stores the wind velocity applied to the cloth, waking it up if it
+    /// changed since the last step
+    pub fn update_wind_velocity(&mut self, wind_velocity: Vec3) {
+        if wind_velocity != self.wind_velocity {
+            self.wind_velocity = wind_velocity;
+            self.wake_up();
+        }
+    }
+
+    /// Applies an `impulse` to the given `points`, changing their velocity
+    /// according to their inverse mass, and wakes up the cloth.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The point ids receiving the impulse
+    /// * `impulse` - The impulse, as a displacement per step of a point with
+    ///   a mass of `1.0`
+    pub fn apply_impulse(&mut self, points: &[usize], impulse: Vec3) {
+        let impulse = Vec3A::from(impulse);
+        for point in points {
+            let inverse_mass = self.inverse_mass(*point);
+            if let Some(previous) = self.previous_point_positions.get_mut(*point) {
+                *previous -= impulse * inverse_mass;
+            } else {
+                log::warn!("Attempted to apply an impulse to missing cloth point {point}");
+            }
+        }
+        self.wake_up();
     }
 
     /// Creates a new cloth from a mesh. Points positions will be directly
@@ -118,45 +553,752 @@ impl Cloth {
         if indices.len() % 3 != 0 {
             log::error!("Mesh indices count is not a multiple of 3, some indices will be skipped",);
         }
-        let mut stick_lengths = HashMap::with_capacity(indices.len() / 3);
-        for truple in indices.chunks_exact(3) {
-            let [a, b, c] = [truple[0], truple[1], truple[2]];
-            let [p_a, p_b, p_c] = [positions[a], positions[b], positions[c]];
-            if !stick_lengths.contains_key(&[b, a]) {
-                stick_lengths.insert([a, b], stick_len.get_len(p_a, p_b));
+        let mut stick_categories: Vec<_> = stick_generation
+            .generate_sticks(&positions, &indices)
+            .into_iter()
+            .collect();
+        stick_categories.sort_unstable_by_key(|(id, _)| *id);
+        let mut sticks = Sticks::default();
+        for ([a, b], category) in stick_categories {
+            let len = stick_len.get_len(positions[a], positions[b]);
+            sticks.insert([a, b], len, stick_mode, category);
+        }
+        let positions: Vec<Vec3A> = positions.into_iter().map(Vec3A::from).collect();
+        let mut cloth = Self {
+            anchored_points,
+            inverse_masses: vec![1.0; positions.len()],
+            point_weights: Vec::new(),
+            current_point_positions: positions.clone(),
+            previous_point_positions: positions.clone(),
+            step_start_point_positions: positions.clone(),
+            frame_start_point_positions: positions,
+            time_accumulator: 0.0,
+            step_count: 0,
+            rest_steps: 0,
+            sleeping: false,
+            wind_velocity: Vec3::ZERO,
+            sticks,
+            stick_batches: Vec::new(),
+            stick_batches_outdated: false,
+            stick_corrections: Vec::new(),
+            xpbd_corrections: Vec::new(),
+            point_corrections: Vec::new(),
+            anchor_pulls: HashMap::default(),
+            broken_anchors: Vec::new(),
+            torn_sticks: Vec::new(),
+            renamed_sticks: Vec::new(),
+            anchor_edits: HashMap::default(),
+            mass_edits: HashMap::default(),
+            aerodynamic_forces: Vec::new(),
+            triangles: indices
+                .chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect(),
+            torn_edges: HashSet::default(),
+            stick_lambdas: Vec::new(),
+            bending_constraints: Vec::new(),
+            bending_stiffness: 0.0,
+            tethers: Vec::new(),
+            tether_stretch: None,
+            volume_constraint: None,
+            self_collision_thickness: None,
+            spatial_hash: SpatialHash::default(),
+            tethered_anchors: Vec::new(),
+            pending_tears: Vec::new(),
+            anchor_targets: HashMap::default(),
+            pending_anchor_changes: Vec::new(),
+            pending_anchor_breaks: Vec::new(),
+            pending_settled: false,
+        };
+        cloth.update_point_weights();
+        cloth.generate_stick_batches();
+        cloth
+    }
+
+    /// Retrieves the cloth sticks
+    ///
+    /// Note: the sticks are automatically generated from mesh data
+    #[inline]
+    #[must_use]
+    pub const fn sticks(&self) -> &Sticks {
+        &self.sticks
+    }
+
+    /// Retrieves the cloth independent stick batches, as stick indexes (See
+    /// [`Sticks`]). No two sticks of a batch share a point, allowing to solve
+    /// them in parallel.
+    ///
+    /// Note: the batches are regenerated on the next sticks update when the
+    /// sticks change
+    #[inline]
+    #[must_use]
+    pub fn stick_batches(&self) -> &[Vec<usize>] {
+        &self.stick_batches
+    }
+
+    /// Adds a new stick between two existing points, or updates the existing
+    /// one. Returns the stick index.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the two connected point ids
+    /// * `length` - the stick target length
+    /// * `mode` - the stick behaviour mode
+    /// * `category` - the stick category
+    pub fn add_stick(
+        &mut self,
+        id: StickId,
+        length: f32,
+        mode: StickMode,
+        category: StickCategory,
+    ) -> usize {
+        self.stick_batches_outdated = true;
+        self.sticks.insert(id, length, mode, category)
+    }
+
+    /// Partitions the cloth sticks into independent batches (See
+    /// [`Self::stick_batches`]) through a greedy graph coloring.
+    pub fn generate_stick_batches(&mut self) {
+        let point_count = self
+            .sticks
+            .ids()
+            .iter()
+            .flatten()
+            .max()
+            .map_or(0, |i| i + 1);
+        let mut point_batches: Vec<Vec<usize>> = vec![Vec::new(); point_count];
+        self.stick_batches.clear();
+        for (index, [a, b]) in self.sticks.ids().iter().enumerate() {
+            let batch = (0..=self.stick_batches.len())
+                .find(|i| !point_batches[*a].contains(i) && !point_batches[*b].contains(i))
+                .unwrap_or(self.stick_batches.len());
+            if batch == self.stick_batches.len() {
+                self.stick_batches.push(Vec::new());
             }
-            if !stick_lengths.contains_key(&[c, b]) {
-                stick_lengths.insert([b, c], stick_len.get_len(p_b, p_c));
+            self.stick_batches[batch].push(index);
+            point_batches[*a].push(batch);
+            point_batches[*b].push(batch);
+        }
+        self.stick_batches_outdated = false;
+        log::debug!(
+            "Generated {} stick batches for {} sticks",
+            self.stick_batches.len(),
+            self.sticks.len()
+        );
+    }
+
+    /// Returns `true` if the given `point` is rigidly anchored, and therefore
+    /// unaffected by physics (See [`VertexAnchor::compliance`])
+    #[inline]
+    #[must_use]
+    pub fn is_pinned(&self, point: usize) -> bool {
+        is_pinned(&self.anchored_points, point)
+    }
+
+    /// Retrieves the inverse mass of the given `point`, `0.0` if the point is
+    /// rigidly anchored
+    #[must_use]
+    pub fn inverse_mass(&self, point: usize) -> f32 {
+        self.point_weights.get(point).copied().unwrap_or(1.0)
+    }
+
+    /// Recomputes the effective inverse mass of every point
+    fn update_point_weights(&mut self) {
+        self.point_weights.clone_from(&self.inverse_masses);
+        for (point, (anchor, _)) in &self.anchored_points {
+            if anchor.is_rigid() {
+                if let Some(weight) = self.point_weights.get_mut(*point) {
+                    *weight = 0.0;
+                }
             }
-            if stick_generation == StickGeneration::Triangles
-                && !stick_lengths.contains_key(&[a, c])
-            {
-                stick_lengths.insert([c, a], stick_len.get_len(p_c, p_a));
+        }
+    }
+
+    /// Recomputes the effective inverse mass of the given `point`, after an
+    /// edit of its mass or anchor
+    fn update_point_weight(&mut self, point: usize) {
+        let weight = if is_pinned(&self.anchored_points, point) {
+            0.0
+        } else {
+            self.inverse_masses.get(point).copied().unwrap_or(1.0)
+        };
+        if let Some(current) = self.point_weights.get_mut(point) {
+            *current = weight;
+        }
+    }
+
+    /// Retrieves the mass of the given `point`, if it exists
+    #[must_use]
+    pub fn point_mass(&self, point: usize) -> Option<f32> {
+        self.inverse_masses.get(point).map(|w| w.recip())
+    }
+
+    /// Sets the mass of the given `point`. An infinite mass makes the point
+    /// unaffected by the constraints.
+    pub fn set_point_mass(&mut self, point: usize, mass: f32) {
+        if mass <= 0.0 {
+            log::warn!("Attempted to set a non positive mass {mass} to cloth point {point}");
+            return;
+        }
+        if let Some(inverse_mass) = self.inverse_masses.get_mut(point) {
+            *inverse_mass = mass.recip();
+            self.mass_edits.insert(point, mass);
+            self.update_point_weight(point);
+        } else {
+            log::warn!("Attempted to set the mass of missing cloth point {point}");
+        }
+    }
+
+    /// Adds an extra `mass` to the given `points`, like weights sewn into a
+    /// curtain hem
+    pub fn add_weight(&mut self, points: &[usize], mass: f32) {
+        for point in points {
+            if let Some(current) = self.point_mass(*point) {
+                self.set_point_mass(*point, current + mass);
+            } else {
+                log::warn!("Attempted to add weight to missing cloth point {point}");
             }
         }
-        let stick_modes = stick_lengths.keys().map(|id| (*id, stick_mode)).collect();
-        Self {
-            anchored_points,
-            current_point_positions: positions.clone(),
-            previous_point_positions: positions,
-            stick_lengths,
-            stick_modes,
+    }
+
+    /// Pins the given `point` to `anchor`, replacing its current anchor if
+    /// any. The point keeps its current position relative to the anchor
+    /// target, unless the anchor ignores the vertex position or defines a
+    /// custom offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The point to pin
+    /// * `anchor` - The new point anchor
+    /// * `target_transform` - The `GlobalTransform` of the anchor target: the
+    ///   [`VertexAnchor::custom_target`] entity if set, the cloth entity
+    ///   otherwise
+    ///
+    /// Returns `false` if the point doesn't exist
+    pub fn pin_point(
+        &mut self,
+        point: usize,
+        anchor: VertexAnchor,
+        target_transform: &GlobalTransform,
+    ) -> bool {
+        let Some(position) = self.current_point_positions.get(point) else {
+            log::warn!("Attempted to pin missing cloth point {point}");
+            return false;
+        };
+        let local_position = target_transform
+            .compute_matrix()
+            .inverse()
+            .transform_point3a(*position)
+            .into();
+        self.insert_anchor(point, anchor, local_position);
+        true
+    }
+
+    /// Pins the given `point` to `anchor` with a `local_position` relative to
+    /// the anchor target, replacing its current anchor if any
+    pub(crate) fn insert_anchor(
+        &mut self,
+        point: usize,
+        anchor: VertexAnchor,
+        local_position: Vec3,
+    ) {
+        let previous = self
+            .anchored_points
+            .insert(point, (anchor, local_position))
+            .map(|(previous, _)| previous);
+        self.update_point_weight(point);
+        self.anchor_edits
+            .insert(point, Some((anchor, local_position)));
+        self.pending_anchor_changes.push(AnchorChange {
+            point,
+            previous,
+            anchor: Some(anchor),
+        });
+        self.wake_up();
+    }
+
+    /// Unpins the given `point`, which keeps its current velocity.
+    ///
+    /// Returns the removed anchor, `None` if the point was not anchored
+    pub fn unpin_point(&mut self, point: usize) -> Option<VertexAnchor> {
+        let (anchor, _) = self.anchored_points.remove(&point)?;
+        self.anchor_targets.remove(&point);
+        self.update_point_weight(point);
+        self.anchor_edits.insert(point, None);
+        self.pending_anchor_changes.push(AnchorChange {
+            point,
+            previous: Some(anchor),
+            anchor: None,
+        });
+        self.wake_up();
+        Some(anchor)
+    }
+
+    /// Forgets the anchor and mass edits, making the current anchors and masses
+    /// the initial cloth state kept on rebuilds (See [`Self::copy_state_from`])
+    pub(crate) fn clear_edits(&mut self) {
+        self.anchor_edits.clear();
+        self.mass_edits.clear();
+    }
+
+    /// Retrieves and clears the anchor changes not yet sent as events
+    pub(crate) fn drain_anchor_changes(&mut self) -> Vec<AnchorChange> {
+        std::mem::take(&mut self.pending_anchor_changes)
+    }
+
+    /// Returns `true` if the cloth fell asleep since the last call
+    pub(crate) fn take_settled(&mut self) -> bool {
+        std::mem::take(&mut self.pending_settled)
+    }
+
+    /// Retrieves and clears the broken anchors not yet sent as events
+    pub(crate) fn drain_anchor_breaks(&mut self) -> Vec<(usize, VertexAnchor)> {
+        std::mem::take(&mut self.pending_anchor_breaks)
+    }
+
+    /// Unpins every point pulled away from its anchor further than the anchor
+    /// break threshold (See [`VertexAnchor::break_threshold`]).
+    ///
+    /// Returns the amount of broken anchors
+    pub fn break_overstretched_anchors(&mut self) -> usize {
+        let mut pulls = std::mem::take(&mut self.anchor_pulls);
+        pulls.clear();
+        pulls.extend(
+            self.anchored_points
+                .iter()
+                .filter(|(_, (anchor, _))| anchor.break_threshold.is_some())
+                .map(|(i, _)| (*i, Vec3A::ZERO)),
+        );
+        if pulls.is_empty() {
+            self.anchor_pulls = pulls;
+            return 0;
+        }
+        // Stretch of the sticks connected to the breakable anchored points
+        for ([a, b], len) in self.sticks.ids().iter().zip(self.sticks.lengths()) {
+            for (point, other) in [(*a, *b), (*b, *a)] {
+                let Some(pull) = pulls.get_mut(&point) else {
+                    continue;
+                };
+                let delta =
+                    self.current_point_positions[other] - self.current_point_positions[point];
+                let distance = delta.length();
+                if distance > *len {
+                    *pull += delta * ((distance - len) / distance);
+                }
+            }
+        }
+        let mut broken = std::mem::take(&mut self.broken_anchors);
+        broken.clear();
+        broken.extend(
+            pulls
+                .iter()
+                .filter(|(point, pull)| {
+                    let (anchor, _) = self.anchored_points[*point];
+                    let distance = if anchor.is_rigid() {
+                        pull.length()
+                    } else {
+                        self.anchor_targets.get(*point).map_or(0.0, |target| {
+                            target.distance(self.current_point_positions[**point])
+                        })
+                    };
+                    anchor
+                        .break_threshold
+                        .is_some_and(|threshold| distance > threshold)
+                })
+                .map(|(point, _)| *point),
+        );
+        self.anchor_pulls = pulls;
+        broken.sort_unstable();
+        for point in &broken {
+            if let Some(anchor) = self.unpin_point(*point) {
+                log::debug!("Anchor of cloth point {point} broke");
+                self.pending_anchor_breaks.push((*point, anchor));
+            }
+        }
+        let count = broken.len();
+        self.broken_anchors = broken;
+        count
+    }
+
+    /// Enables cloth self collision with the given `thickness`, the minimum
+    /// distance between two cloth points not connected by a stick
+    pub fn set_self_collision(&mut self, thickness: f32) {
+        self.self_collision_thickness = Some(thickness);
+        self.spatial_hash = SpatialHash::new(thickness);
+    }
+
+    /// Generates the cloth volume preservation constraint from the mesh
+    /// `indices`, using the current enclosed volume as rest volume.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - the closed mesh indices
+    /// * `target_ratio` - Target volume ratio of the rest volume
+    /// * `pressure` - Pressure coefficient, between 0.0 and 1.0
+    pub fn generate_volume_constraint(
+        &mut self,
+        indices: &[u32],
+        target_ratio: f32,
+        pressure: f32,
+    ) {
+        let indices: Vec<usize> = indices.iter().map(|i| *i as usize).collect();
+        let constraint = VolumeConstraint::new(
+            &self.current_point_positions,
+            &indices,
+            target_ratio,
+            pressure,
+        );
+        log::debug!(
+            "Generated cloth volume constraint: {}",
+            constraint.rest_volume
+        );
+        self.volume_constraint = Some(constraint);
+    }
+
+    /// Retrieves the current volume enclosed by the cloth, if it has a volume
+    /// constraint
+    #[must_use]
+    pub fn current_volume(&self) -> Option<f32> {
+        self.volume_constraint
+            .as_ref()
+            .map(|c| c.compute_volume(&self.current_point_positions))
+    }
+
+    /// Sets the target volume ratio of the cloth volume constraint, allowing
+    /// to inflate (above `1.0`) or deflate (below `1.0`) the cloth at runtime.
+    ///
+    /// Note: Has no effect if the cloth has no volume constraint
+    pub fn set_volume_ratio(&mut self, target_ratio: f32) {
+        if let Some(constraint) = &mut self.volume_constraint {
+            constraint.target_ratio = target_ratio;
+        } else {
+            log::warn!("Attempted to set the volume ratio of a cloth without volume constraint");
+        }
+    }
+
+    /// Sets the pressure coefficient of the cloth volume constraint.
+    ///
+    /// Note: Has no effect if the cloth has no volume constraint
+    pub fn set_volume_pressure(&mut self, pressure: f32) {
+        if let Some(constraint) = &mut self.volume_constraint {
+            constraint.pressure = pressure;
+        } else {
+            log::warn!("Attempted to set the volume pressure of a cloth without volume constraint");
+        }
+    }
+
+    /// Generates the cloth long range attachment constraints, linking every
+    /// free point to its nearest anchored point. Distances are measured along
+    /// the cloth sticks (See [`Tether::generate`])
+    pub fn generate_tethers(&mut self) {
+        self.tethered_anchors = self.anchored_points.keys().copied().collect();
+        self.tethered_anchors.sort_unstable();
+        self.tethers = Tether::generate(
+            self.current_point_positions.len(),
+            self.tethered_anchors.iter().copied(),
+            self.sticks
+                .ids()
+                .iter()
+                .copied()
+                .zip(self.sticks.lengths().iter().copied()),
+        );
+        log::debug!("Generated {} cloth tethers", self.tethers.len());
+    }
+
+    /// Generates the cloth bending constraints from the mesh `indices`, for
+    /// every pair of triangles sharing an edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - the mesh indices
+    /// * `bending_angle` - The bending rest angle option
+    /// * `bending_stiffness` - The bending stiffness, between 0.0 and 1.0
+    pub fn generate_bending_constraints(
+        &mut self,
+        indices: &[u32],
+        bending_angle: BendingAngle,
+        bending_stiffness: f32,
+    ) {
+        let indices: Vec<usize> = indices.iter().map(|i| *i as usize).collect();
+        let positions: Vec<Vec3> = self
+            .current_point_positions
+            .iter()
+            .copied()
+            .map(Vec3::from)
+            .collect();
+        self.bending_constraints = BendingConstraint::generate(&positions, &indices, bending_angle);
+        self.bending_stiffness = bending_stiffness;
+        log::debug!(
+            "Generated {} cloth bending constraints",
+            self.bending_constraints.len()
+        );
+    }
+
+    /// Changes the stick behaviour to `new_mode` for `sticks`
+    pub fn edit_stick_modes(&mut self, sticks: &[StickId], new_mode: StickMode) {
+        log::debug!("Editing {} sticks: {new_mode:#?}", sticks.len());
+        for id in sticks {
+            self.sticks.index(*id).map_or_else(
+                || {
+                    log::warn!("Attempted to edit missing stick `{id:?}` behaviour");
+                },
+                |index| {
+                    self.sticks.modes_mut()[index] = new_mode;
+                },
+            );
+        }
+    }
+
+    /// Changes the stick behaviour to `new_mode` for every stick of the given
+    /// `category`
+    pub fn edit_category_stick_modes(&mut self, category: StickCategory, new_mode: StickMode) {
+        log::debug!("Editing {category:?} sticks: {new_mode:#?}");
+        for index in 0..self.sticks.len() {
+            if self.sticks.categories()[index] == category {
+                self.sticks.modes_mut()[index] = new_mode;
+            }
+        }
+    }
+
+    /// Changes the target length of every stick of the given `category`,
+    /// computed from the current point positions with `stick_len`
+    pub fn edit_category_stick_lengths(&mut self, category: StickCategory, stick_len: StickLen) {
+        log::debug!("Editing {category:?} sticks length: {stick_len:#?}");
+        for index in 0..self.sticks.len() {
+            if self.sticks.categories()[index] == category {
+                let [a, b] = self.sticks.ids()[index];
+                self.sticks.lengths_mut()[index] = stick_len.get_len(
+                    self.current_point_positions[a].into(),
+                    self.current_point_positions[b].into(),
+                );
+            }
+        }
+    }
+
+    /// Returns `true` if at least one stick has a tearing threshold (See
+    /// [`StickMode::tear_threshold`])
+    #[must_use]
+    pub fn is_tearable(&self) -> bool {
+        self.sticks
+            .modes()
+            .iter()
+            .any(|mode| mode.tear_threshold().is_some())
+    }
+
+    /// Tears every stick stretched above its tearing threshold (See
+    /// [`StickMode::tear_threshold`]).
+    ///
+    /// Returns the amount of torn sticks
+    pub fn tear_overstretched_sticks(&mut self) -> usize {
+        let mut torn = std::mem::take(&mut self.torn_sticks);
+        torn.clear();
+        torn.extend(
+            (0..self.sticks.len())
+                .filter(|i| {
+                    self.sticks.modes()[*i]
+                        .tear_threshold()
+                        .is_some_and(|threshold| {
+                            let [a, b] = self.sticks.ids()[*i];
+                            let max_len = self.sticks.lengths()[*i] * threshold;
+                            self.current_point_positions[a]
+                                .distance_squared(self.current_point_positions[b])
+                                > max_len * max_len
+                        })
+                })
+                .map(|i| self.sticks.ids()[i]),
+        );
+        let mut count = 0;
+        for i in 0..torn.len() {
+            if self.tear_stick(torn[i]) {
+                count += 1;
+            }
+            // The split points sticks were renamed
+            for (old, new) in &self.renamed_sticks {
+                if let Some(id) = torn[i + 1..].iter_mut().find(|id| *id == old) {
+                    *id = *new;
+                }
+            }
+        }
+        self.torn_sticks = torn;
+        count
+    }
+
+    /// Tears the given stick, removing it from the cloth. Cloth points are
+    /// duplicated along the tear, splitting the cloth topology.
+    ///
+    /// Returns `false` if the stick doesn't exist
+    pub fn tear_stick(&mut self, [a, b]: StickId) -> bool {
+        self.renamed_sticks.clear();
+        let Some(index) = self.sticks.index([a, b]) else {
+            log::warn!("Attempted to tear missing stick `{:?}`", [a, b]);
+            return false;
+        };
+        let id = self.sticks.ids()[index];
+        self.sticks.remove(id);
+        self.stick_batches_outdated = true;
+        self.wake_up();
+        let edge = [a.min(b), a.max(b)];
+        self.torn_edges.insert(edge);
+        self.bending_constraints
+            .retain(|c| [c.points[0].min(c.points[1]), c.points[0].max(c.points[1])] != edge);
+        let triangles = self
+            .triangles
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.contains(&a) && t.contains(&b))
+            .map(|(i, _)| i)
+            .collect();
+        let mut splits = self.split_point(a);
+        splits.extend(self.split_point(b));
+        if !splits.is_empty() && self.tether_stretch.is_some() {
+            self.generate_tethers();
+        }
+        log::debug!("Tore stick {id:?}, duplicating {} points", splits.len());
+        self.pending_tears.push(ClothTear {
+            stick: id,
+            triangles,
+            splits,
+        });
+        true
+    }
+
+    /// Retrieves and clears the tears not yet applied to the cloth rendering
+    pub(crate) fn drain_tears(&mut self) -> Vec<ClothTear> {
+        std::mem::take(&mut self.pending_tears)
+    }
+
+    /// Duplicates `point` for every group of its adjacent triangles which are
+    /// disconnected by torn edges.
+    fn split_point(&mut self, point: usize) -> Vec<PointSplit> {
+        fn root(groups: &[usize], mut i: usize) -> usize {
+            while groups[i] != i {
+                i = groups[i];
+            }
+            i
+        }
+
+        let fan: Vec<usize> = self
+            .triangles
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.contains(&point))
+            .map(|(i, _)| i)
+            .collect();
+        let mut groups: Vec<usize> = (0..fan.len()).collect();
+        for i in 0..fan.len() {
+            for j in (i + 1)..fan.len() {
+                let connected = self.triangles[fan[i]].iter().any(|w| {
+                    *w != point
+                        && self.triangles[fan[j]].contains(w)
+                        && !self.torn_edges.contains(&[point.min(*w), point.max(*w)])
+                });
+                if connected {
+                    let (root_i, root_j) = (root(&groups, i), root(&groups, j));
+                    groups[root_j] = root_i;
+                }
+            }
+        }
+        let mut fan_groups: Vec<(usize, Vec<usize>)> = Vec::new();
+        for (i, triangle) in fan.iter().enumerate() {
+            let group = root(&groups, i);
+            match fan_groups.iter_mut().find(|(g, _)| *g == group) {
+                Some((_, triangles)) => triangles.push(*triangle),
+                None => fan_groups.push((group, vec![*triangle])),
+            }
+        }
+        let mut splits = Vec::new();
+        for (_, triangles) in fan_groups.into_iter().skip(1) {
+            let new = self.duplicate_point(point);
+            let vertices: HashSet<usize> = triangles
+                .iter()
+                .flat_map(|t| self.triangles[*t])
+                .filter(|w| *w != point)
+                .collect();
+            for t in &triangles {
+                for p in &mut self.triangles[*t] {
+                    if *p == point {
+                        *p = new;
+                    }
+                }
+                if let Some(volume_triangle) = self
+                    .volume_constraint
+                    .as_mut()
+                    .and_then(|v| v.triangles.get_mut(*t))
+                {
+                    *volume_triangle = self.triangles[*t];
+                }
+            }
+            self.move_point_sticks(point, new, &vertices);
+            let torn_edges: Vec<_> = vertices
+                .iter()
+                .filter(|w| self.torn_edges.contains(&[point.min(**w), point.max(**w)]))
+                .map(|w| [new.min(*w), new.max(*w)])
+                .collect();
+            self.torn_edges.extend(torn_edges);
+            for constraint in &mut self.bending_constraints {
+                if constraint.points.contains(&point)
+                    && constraint
+                        .points
+                        .iter()
+                        .all(|p| *p == point || vertices.contains(p))
+                {
+                    for p in &mut constraint.points {
+                        if *p == point {
+                            *p = new;
+                        }
+                    }
+                }
+            }
+            splits.push(PointSplit {
+                original: point,
+                new,
+                triangles,
+            });
+        }
+        splits
+    }
+
+    /// Moves the sticks between `point` and `vertices` to the duplicated
+    /// `new` point
+    fn move_point_sticks(&mut self, point: usize, new: usize, vertices: &HashSet<usize>) {
+        let moved_sticks: Vec<(usize, StickId)> = self
+            .sticks
+            .ids()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, [x, y])| {
+                if *x == point && vertices.contains(y) {
+                    Some((i, [new, *y]))
+                } else if *y == point && vertices.contains(x) {
+                    Some((i, [*x, new]))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        for (index, new_id) in moved_sticks {
+            self.renamed_sticks.push((self.sticks.ids()[index], new_id));
+            self.sticks.set_id(index, new_id);
         }
     }
 
-    /// Changes the stick behaviour to `new_mode` for `sticks`
-    pub fn edit_stick_modes(&mut self, sticks: &[StickId], new_mode: StickMode) {
-        log::debug!("Editing {} sticks: {new_mode:#?}", sticks.len());
-        for id in sticks {
-            self.stick_modes.get_mut(id).map_or_else(
-                || {
-                    log::warn!("Attempted to edit missing stick `{id:?}` behaviour");
-                },
-                |mode| {
-                    *mode = new_mode;
-                },
-            );
+    /// Appends a copy of `point` to the cloth points and returns its id
+    fn duplicate_point(&mut self, point: usize) -> usize {
+        self.current_point_positions
+            .push(self.current_point_positions[point]);
+        self.previous_point_positions
+            .push(self.previous_point_positions[point]);
+        self.step_start_point_positions
+            .push(self.step_start_point_positions[point]);
+        self.frame_start_point_positions
+            .push(self.frame_start_point_positions[point]);
+        self.inverse_masses.push(self.inverse_masses[point]);
+        self.point_weights.push(self.point_weights[point]);
+        let id = self.current_point_positions.len() - 1;
+        if let Some(anchor) = self.anchored_points.get(&point).copied() {
+            self.anchored_points.insert(id, anchor);
         }
+        id
     }
 
     /// Adds an extra point to the cloth (Not included in the base mesh) and
@@ -169,19 +1311,27 @@ impl Cloth {
         transform_matrix: &Mat4,
         connects_to: impl Fn(usize, &Vec3) -> bool,
     ) -> (usize, Vec<StickId>) {
-        let center = transform_matrix.transform_point3(pos);
+        let center = transform_matrix.transform_point3a(pos.into());
         self.current_point_positions.push(center);
         self.previous_point_positions.push(center);
+        self.step_start_point_positions.push(center);
+        self.frame_start_point_positions.push(center);
+        self.inverse_masses.push(1.0);
+        self.point_weights.push(1.0);
         let id = self.current_point_positions.len().saturating_sub(1);
         let sticks: Vec<_> = self
             .current_point_positions
             .iter()
             .enumerate()
-            .filter(|(i, p)| connects_to(*i, p))
+            .filter(|(i, p)| connects_to(*i, &Vec3::from(**p)))
             .map(|(i, p)| {
                 let stick_id = [id, i];
-                self.stick_modes.insert(stick_id, stick_mode);
-                self.stick_lengths.insert(stick_id, p.distance(center));
+                self.sticks.insert(
+                    stick_id,
+                    p.distance(center),
+                    stick_mode,
+                    StickCategory::Structural,
+                );
                 stick_id
             })
             .collect();
@@ -191,43 +1341,111 @@ impl Cloth {
         );
         if let Some(anchor) = anchor {
             self.anchored_points.insert(id, (anchor, pos));
+            self.update_point_weight(id);
         }
+        self.stick_batches_outdated = true;
         (id, sticks)
     }
 
-    /// Solves cloth points collisions, moving them outside of colliders
+    /// Solves cloth points collisions, moving them outside of colliders.
+    /// Wakes up the cloth if a point is moved.
     ///
     /// # Arguments
     ///
     /// * `solve_point` - function taking a cloth point and returning the new
     ///   solved point
     pub fn solve_collisions(&mut self, solve_point: impl Fn(&Vec3) -> Option<Vec3>) {
+        let mut collided = false;
+        for (point, new_point) in self
+            .current_point_positions
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _p)| is_free(&self.point_weights, &self.anchored_points, *i))
+            .filter_map(|(_i, p)| solve_point(&Vec3::from(*p)).map(|np| (p, Vec3A::from(np))))
+        {
+            collided |= point.distance_squared(new_point) > f32::EPSILON;
+            *point = new_point;
+        }
+        if collided && self.sleeping {
+            self.wake_up();
+        }
+    }
+
+    /// Solves cloth points swept collisions, moving the points which passed
+    /// through a collider since the beginning of the frame (See
+    /// [`Self::begin_frame`]) back in front of it.
+    /// Wakes up the cloth if a point is moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `solve_motion` - function taking the frame start and current
+    ///   position of a cloth point and returning the new solved point
+    pub fn solve_swept_collisions(&mut self, solve_motion: impl Fn(&Vec3, &Vec3) -> Option<Vec3>) {
+        let mut collided = false;
         for (point, new_point) in self
             .current_point_positions
             .iter_mut()
+            .zip(&self.frame_start_point_positions)
             .enumerate()
-            .filter(|(i, _p)| !self.anchored_points.contains_key(i))
-            .filter_map(|(_i, p)| solve_point(p).map(|np| (p, np)))
+            .filter(|(i, _)| is_free(&self.point_weights, &self.anchored_points, *i))
+            .filter_map(|(_i, (p, start))| {
+                solve_motion(&Vec3::from(*start), &Vec3::from(*p)).map(|np| (p, Vec3A::from(np)))
+            })
         {
+            collided |= point.distance_squared(new_point) > f32::EPSILON;
             *point = new_point;
         }
+        if collided && self.sleeping {
+            self.wake_up();
+        }
     }
 
-    /// Updates the cloth anchored points
+    /// Updates the cloth anchored points, rigidly anchored points are moved
+    /// to their anchor position and soft anchored points are pulled toward it
+    /// through a compliant constraint (See [`VertexAnchor::compliance`]).
+    /// Wakes up the cloth if an anchor moved.
     ///
     /// # Arguments
     ///
     /// * `transform` - The `GlobalTransform` associated to the cloth entity
     /// * `anchor_query` - A function allowing to retrieve the `GlobalTransform`
     ///   of a given entity
+    /// * `delta_time` - Elapsed time since last step in seconds, keeping the
+    ///   soft anchors stiffness independent of the frame rate
     pub fn update_anchored_points<'a>(
         &mut self,
         transform: &GlobalTransform,
         anchor_query: impl Fn(Entity) -> Option<&'a GlobalTransform>,
+        delta_time: f32,
     ) {
+        let mut moved = false;
         for (i, (anchor, inital_pos)) in &self.anchored_points {
-            self.current_point_positions[*i] =
-                anchor.get_position(*inital_pos, transform, &anchor_query);
+            let target = anchor
+                .get_position(*inital_pos, transform, &anchor_query)
+                .into();
+            moved |= self.anchor_targets.insert(*i, target) != Some(target);
+        }
+        if self.sleeping {
+            if !moved {
+                return;
+            }
+            self.wake_up();
+        }
+        let squared_delta = (delta_time * delta_time).max(f32::EPSILON);
+        for (i, (anchor, _)) in &self.anchored_points {
+            let target = self.anchor_targets[i];
+            let point = &mut self.current_point_positions[*i];
+            let Some(compliance) = anchor.compliance else {
+                *point = target;
+                continue;
+            };
+            // XPBD distance to target constraint, its Lagrange multiplier is
+            // reset every step
+            let inverse_mass = self.inverse_masses[*i];
+            let weight = inverse_mass + compliance.max(0.0) / squared_delta;
+            if weight > 0.0 {
+                *point += (target - *point) * (inverse_mass / weight);
+            }
         }
     }
 
@@ -239,90 +1457,438 @@ impl Cloth {
     /// * `friction` - Friction to apply to the points velocity
     /// * `acceleration` - Global acceleration force (gravity, wind, etc)
     pub fn update_points(&mut self, friction: f32, acceleration: Vec3) {
-        let position_cache = self.current_point_positions.clone();
-        for (i, point) in self.current_point_positions.iter_mut().enumerate() {
-            if !self.anchored_points.contains_key(&i) {
-                let velocity = self
-                    .previous_point_positions
-                    .get(i)
-                    .map_or(Vec3::ZERO, |prev| *point - *prev);
-                *point += velocity * friction + acceleration;
+        let acceleration = Vec3A::from(acceleration);
+        for (i, (point, previous)) in self
+            .current_point_positions
+            .iter_mut()
+            .zip(&mut self.previous_point_positions)
+            .enumerate()
+        {
+            let position = *point;
+            if is_free(&self.point_weights, &self.anchored_points, i) {
+                *point += (position - *previous) * friction + acceleration;
+            }
+            *previous = position;
+        }
+    }
+
+    /// Applies the aerodynamic drag and lift forces of the wind on every
+    /// cloth triangle (See [`aerodynamics::triangle_force`]), spread on the
+    /// triangle points according to their mass. Should be called after
+    /// [`Self::update_points`].
+    ///
+    /// # Arguments
+    ///
+    /// * `wind_velocity` - The air velocity
+    /// * `drag_coefficient` - The drag coefficient
+    /// * `lift_coefficient` - The lift coefficient
+    /// * `delta_time` - Elapsed time since last step in seconds, used to
+    ///   compute the points velocity
+    /// * `smooth_value` - Coefficient applied to the resulting accelerations
+    ///   (See [`ClothConfig::smooth_value`])
+    ///
+    /// [`ClothConfig::smooth_value`]: crate::config::ClothConfig::smooth_value
+    pub fn update_aerodynamics(
+        &mut self,
+        wind_velocity: Vec3,
+        drag_coefficient: f32,
+        lift_coefficient: f32,
+        delta_time: f32,
+        smooth_value: f32,
+    ) {
+        let wind_velocity = Vec3A::from(wind_velocity);
+        let velocity_scale = if delta_time > f32::EPSILON {
+            delta_time.recip()
+        } else {
+            0.0
+        };
+        let mut forces = std::mem::take(&mut self.aerodynamic_forces);
+        forces.clear();
+        forces.resize(self.current_point_positions.len(), Vec3A::ZERO);
+        for triangle in &self.triangles {
+            let points = triangle.map(|i| self.current_point_positions[i]);
+            let velocity = triangle
+                .iter()
+                .map(|i| self.current_point_positions[*i] - self.previous_point_positions[*i])
+                .sum::<Vec3A>()
+                * (velocity_scale / 3.0);
+            let force = aerodynamics::triangle_force(
+                points,
+                velocity,
+                wind_velocity,
+                drag_coefficient,
+                lift_coefficient,
+            ) / 3.0;
+            for i in triangle {
+                forces[*i] += force;
             }
         }
-        self.previous_point_positions = position_cache;
+        for (i, (point, force)) in self
+            .current_point_positions
+            .iter_mut()
+            .zip(&forces)
+            .enumerate()
+        {
+            *point += *force * (self.point_weights[i] * smooth_value);
+        }
+        self.aerodynamic_forces = forces;
+    }
+
+    /// Computes the position corrections projecting the points of the stick
+    /// at `index`, if the stick is not satisfied
+    fn project_stick(&self, index: usize) -> Option<[Vec3A; 2]> {
+        let [id_a, id_b] = self.sticks.ids()[index];
+        let (Some(position_a), Some(position_b)) = (
+            self.current_point_positions.get(id_a),
+            self.current_point_positions.get(id_b),
+        ) else {
+            log::warn!(
+                "Failed to retrieve the Cloth points of stick {:?}",
+                [id_a, id_b]
+            );
+            return None;
+        };
+        let [weight_a, weight_b] = [id_a, id_b].map(|i| self.point_weights[i]);
+        let total_weight = weight_a + weight_b;
+        if total_weight <= 0.0 {
+            return None;
+        }
+        let delta = *position_b - *position_a;
+        let distance = delta.length();
+        let target_len =
+            self.sticks.modes()[index].constrained_len(distance, self.sticks.lengths()[index])?;
+        if distance <= f32::EPSILON {
+            log::warn!(
+                "Failed handle stick between points {} and {} which are too close to each other",
+                id_a,
+                id_b
+            );
+            return None;
+        }
+        let correction = delta * ((distance - target_len) / (distance * total_weight));
+        Some([correction * weight_a, -correction * weight_b])
     }
 
-    /// Applies the cloth sticks constraints
+    /// Applies the cloth sticks constraints. Each batch of
+    /// [`Self::stick_batches`] is solved in parallel on `task_pool`.
     ///
     /// # Arguments
     ///
     /// * `depth` - Number of sticks constraint iterations
-    pub fn update_sticks(&mut self, depth: u8) {
+    /// * `task_pool` - The task pool solving the large batches, usually the
+    ///   `ComputeTaskPool`
+    pub fn update_sticks(&mut self, depth: u8, task_pool: &TaskPool) {
+        if self.stick_batches_outdated {
+            self.generate_stick_batches();
+        }
+        let mut corrections = std::mem::take(&mut self.stick_corrections);
         for _ in 0..depth {
-            for ([id_a, id_b], target_len) in &self.stick_lengths {
-                let (position_a, fixed_a) =
-                    get_point!(*id_a, self.current_point_positions, self.anchored_points);
-                let (position_b, fixed_b) =
-                    get_point!(*id_b, self.current_point_positions, self.anchored_points);
-                if fixed_a && fixed_b {
-                    continue;
+            for batch in &self.stick_batches {
+                corrections.clear();
+                corrections.extend(batch.iter().map(|i| (*i, None)));
+                compute_stick_corrections(task_pool, &mut corrections, |i| self.project_stick(i));
+                for (index, correction) in &corrections {
+                    let Some([correction_a, correction_b]) = correction else {
+                        continue;
+                    };
+                    let [id_a, id_b] = self.sticks.ids()[*index];
+                    self.current_point_positions[id_a] += *correction_a;
+                    self.current_point_positions[id_b] += *correction_b;
                 }
-                let target_len = match self.stick_modes[&[*id_a, *id_b]] {
-                    StickMode::Fixed => *target_len,
-                    StickMode::Spring {
-                        min_percent,
-                        max_percent,
-                    } => {
-                        let dist = position_a.distance(position_b) / *target_len;
-                        if dist < min_percent {
-                            *target_len * min_percent
-                        } else if dist > max_percent {
-                            *target_len * max_percent
-                        } else {
-                            continue;
-                        }
-                    }
+            }
+        }
+        self.stick_corrections = corrections;
+    }
+
+    /// Applies the cloth sticks constraints through Jacobi iterations: every
+    /// stick correction is computed from the same point positions, then the
+    /// corrections are averaged per point and applied. The result doesn't
+    /// depend on the sticks order.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Number of sticks constraint iterations
+    /// * `task_pool` - The task pool computing the corrections of large
+    ///   cloths, usually the `ComputeTaskPool`
+    pub fn update_sticks_jacobi(&mut self, depth: u8, task_pool: &TaskPool) {
+        let mut corrections = std::mem::take(&mut self.stick_corrections);
+        let mut point_corrections = std::mem::take(&mut self.point_corrections);
+        for _ in 0..depth {
+            corrections.clear();
+            corrections.extend((0..self.sticks.len()).map(|i| (i, None)));
+            compute_stick_corrections(task_pool, &mut corrections, |i| self.project_stick(i));
+            point_corrections.clear();
+            point_corrections.resize(self.current_point_positions.len(), (Vec3A::ZERO, 0.0));
+            for (index, correction) in &corrections {
+                let Some([correction_a, correction_b]) = correction else {
+                    continue;
                 };
-                let center = (position_b + position_a) / 2.0;
-                let direction = match (position_b - position_a).try_normalize() {
-                    None => {
-                        log::warn!(
-                            "Failed handle stick between points {} and {} which are too close to \
-                             each other",
-                            *id_a,
-                            *id_b
-                        );
-                        continue;
-                    }
-                    Some(dir) => dir * target_len / 2.0,
+                let [id_a, id_b] = self.sticks.ids()[*index];
+                point_corrections[id_a].0 += *correction_a;
+                point_corrections[id_a].1 += 1.0;
+                point_corrections[id_b].0 += *correction_b;
+                point_corrections[id_b].1 += 1.0;
+            }
+            for (point, (correction, count)) in self
+                .current_point_positions
+                .iter_mut()
+                .zip(&point_corrections)
+            {
+                if *count > 0.0 {
+                    *point += *correction / *count;
+                }
+            }
+        }
+        self.stick_corrections = corrections;
+        self.point_corrections = point_corrections;
+    }
+
+    /// Applies the cloth long range attachment constraints, moving back the
+    /// points too far from their anchor. The tethers are regenerated if the
+    /// anchored points changed.
+    pub fn update_tethers(&mut self) {
+        let Some(stretch) = self.tether_stretch else {
+            return;
+        };
+        if self.tethered_anchors.len() != self.anchored_points.len()
+            || self
+                .tethered_anchors
+                .iter()
+                .any(|i| !self.anchored_points.contains_key(i))
+        {
+            self.generate_tethers();
+        }
+        for tether in &self.tethers {
+            if !is_free(&self.point_weights, &self.anchored_points, tether.point) {
+                continue;
+            }
+            let anchor = self.current_point_positions[tether.anchor];
+            let point = &mut self.current_point_positions[tether.point];
+            let max_distance = tether.rest_distance * stretch;
+            let delta = *point - anchor;
+            if delta.length_squared() > max_distance * max_distance {
+                *point = anchor + delta.normalize() * max_distance;
+            }
+        }
+    }
+
+    /// Applies the cloth volume constraint, if any. The pressure is corrected
+    /// according to `depth` to keep a consistent behaviour.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Number of volume constraint iterations
+    pub fn update_volume(&mut self, depth: u8) {
+        let Some(constraint) = &mut self.volume_constraint else {
+            return;
+        };
+        if constraint.pressure <= 0.0 || depth == 0 {
+            return;
+        }
+        let stiffness =
+            1.0 - (1.0 - constraint.pressure.clamp(0.0, 1.0)).powf(1.0 / f32::from(depth));
+        for _ in 0..depth {
+            constraint.solve(
+                &mut self.current_point_positions,
+                |i| self.point_weights[i],
+                stiffness,
+            );
+        }
+    }
+
+    /// Applies the cloth self collisions, if enabled, pushing apart the points
+    /// closer than [`Self::self_collision_thickness`] which are not connected
+    /// by a stick. The spatial hash is rebuilt from the current positions.
+    pub fn update_self_collisions(&mut self) {
+        let Some(thickness) = self.self_collision_thickness else {
+            return;
+        };
+        if thickness <= 0.0 {
+            return;
+        }
+        if (self.spatial_hash.cell_size - thickness).abs() > f32::EPSILON {
+            self.spatial_hash = SpatialHash::new(thickness);
+        }
+        self.spatial_hash.rebuild(&self.current_point_positions);
+        for a in 0..self.current_point_positions.len() {
+            let weight_a = self.point_weights[a];
+            let position = self.current_point_positions[a];
+            for b in self.spatial_hash.neighbours(position) {
+                if b <= a || self.sticks.contains([a, b]) {
+                    continue;
+                }
+                let weight_b = self.point_weights[b];
+                let total_weight = weight_a + weight_b;
+                if total_weight <= 0.0 {
+                    continue;
+                }
+                let delta = self.current_point_positions[b] - self.current_point_positions[a];
+                let distance = delta.length();
+                if distance >= thickness || distance <= f32::EPSILON {
+                    continue;
+                }
+                let correction = delta / distance * (thickness - distance) / total_weight;
+                self.current_point_positions[a] -= correction * weight_a;
+                self.current_point_positions[b] += correction * weight_b;
+            }
+        }
+    }
+
+    /// Applies the cloth bending constraints. The bending stiffness is
+    /// corrected according to `depth` to keep a consistent behaviour.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Number of bending constraint iterations
+    pub fn update_bending(&mut self, depth: u8) {
+        if self.bending_constraints.is_empty() || self.bending_stiffness <= 0.0 || depth == 0 {
+            return;
+        }
+        let stiffness =
+            1.0 - (1.0 - self.bending_stiffness.clamp(0.0, 1.0)).powf(1.0 / f32::from(depth));
+        for _ in 0..depth {
+            for constraint in &self.bending_constraints {
+                let points = constraint
+                    .points
+                    .map(|i| Vec3::from(self.current_point_positions[i]));
+                let weights = constraint.points.map(|i| self.point_weights[i]);
+                let Some(corrections) = constraint.corrections(points, weights) else {
+                    continue;
                 };
-                if !fixed_a {
-                    self.current_point_positions[*id_a] = if fixed_b {
-                        position_b - direction * 2.0
-                    } else {
-                        center - direction
-                    };
+                for (i, correction) in constraint.points.into_iter().zip(corrections) {
+                    self.current_point_positions[i] += Vec3A::from(correction) * stiffness;
                 }
-                if !fixed_b {
-                    self.current_point_positions[*id_b] = if fixed_a {
-                        position_a + direction * 2.0
-                    } else {
-                        center + direction
+            }
+        }
+    }
+
+    /// Computes the Lagrange multiplier and position corrections of the
+    /// points of the stick at `index` for the XPBD solver, if the stick is
+    /// not satisfied
+    fn solve_stick_xpbd(&self, index: usize, squared_dt: f32) -> Option<XpbdCorrection> {
+        let [id_a, id_b] = self.sticks.ids()[index];
+        let (Some(position_a), Some(position_b)) = (
+            self.current_point_positions.get(id_a),
+            self.current_point_positions.get(id_b),
+        ) else {
+            log::warn!(
+                "Failed to retrieve the Cloth points of stick {:?}",
+                [id_a, id_b]
+            );
+            return None;
+        };
+        let [weight_a, weight_b] = [id_a, id_b].map(|i| self.point_weights[i]);
+        if weight_a + weight_b <= 0.0 {
+            return None;
+        }
+        let mode = self.sticks.modes()[index];
+        let delta = *position_b - *position_a;
+        let distance = delta.length();
+        let target_len = mode.constrained_len(distance, self.sticks.lengths()[index])?;
+        if distance <= f32::EPSILON {
+            log::warn!(
+                "Failed handle stick between points {} and {} which are too close to each other",
+                id_a,
+                id_b
+            );
+            return None;
+        }
+        let direction = delta / distance;
+        let alpha = mode.compliance() / squared_dt;
+        let delta_lambda = alpha.mul_add(-self.stick_lambdas[index], target_len - distance)
+            / (weight_a + weight_b + alpha);
+        Some((
+            delta_lambda,
+            [
+                -direction * (weight_a * delta_lambda),
+                direction * (weight_b * delta_lambda),
+            ],
+        ))
+    }
+
+    /// Applies the cloth sticks constraints using the XPBD (Extended Position
+    /// Based Dynamics) solver. Each stick compliance (See
+    /// [`StickMode::compliance`]) is taken into account, making the sticks
+    /// stiffness independent of `depth` and `delta_time`. Each batch of
+    /// [`Self::stick_batches`] is solved in parallel on `task_pool`.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Number of sticks constraint iterations
+    /// * `delta_time` - Elapsed time since last step in seconds
+    /// * `task_pool` - The task pool solving the large batches, usually the
+    ///   `ComputeTaskPool`
+    pub fn update_sticks_xpbd(&mut self, depth: u8, delta_time: f32, task_pool: &TaskPool) {
+        if delta_time <= f32::EPSILON {
+            return;
+        }
+        if self.stick_batches_outdated {
+            self.generate_stick_batches();
+        }
+        let squared_dt = delta_time * delta_time;
+        self.stick_lambdas.clear();
+        self.stick_lambdas.resize(self.sticks.len(), 0.0);
+        let mut corrections = std::mem::take(&mut self.xpbd_corrections);
+        for _ in 0..depth {
+            for batch in &self.stick_batches {
+                corrections.clear();
+                corrections.extend(batch.iter().map(|i| (*i, None)));
+                compute_stick_corrections(task_pool, &mut corrections, |i| {
+                    self.solve_stick_xpbd(i, squared_dt)
+                });
+                for (index, correction) in &corrections {
+                    let Some((delta_lambda, [correction_a, correction_b])) = correction else {
+                        continue;
                     };
+                    let [id_a, id_b] = self.sticks.ids()[*index];
+                    self.stick_lambdas[*index] += *delta_lambda;
+                    self.current_point_positions[id_a] += *correction_a;
+                    self.current_point_positions[id_b] += *correction_b;
                 }
             }
         }
+        self.xpbd_corrections = corrections;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mesh::rectangle_mesh;
+    use crate::{components::cloth_rendering::ClothRendering, mesh::rectangle_mesh};
+    use bevy::{math::Vec4, tasks::TaskPoolBuilder};
+
+    /// Builds a cloth from the given points and triangles, with quad sticks
+    fn quad_cloth(positions: &[Vec3], indices: &[u32]) -> Cloth {
+        anchored_cloth(positions, indices, HashMap::default())
+    }
+
+    /// Builds a cloth from the given points, triangles and anchors, with quad
+    /// sticks
+    fn anchored_cloth(
+        positions: &[Vec3],
+        indices: &[u32],
+        anchored_points: HashMap<usize, VertexAnchor>,
+    ) -> Cloth {
+        Cloth::new(
+            positions,
+            indices,
+            anchored_points,
+            StickGeneration::Quads,
+            StickLen::Auto,
+            StickMode::default(),
+            &Mat4::IDENTITY,
+        )
+    }
+
+    /// Builds a cloth from a `size` rectangle mesh, with quad sticks
+    fn rectangle_cloth(size: (usize, usize)) -> Cloth {
+        let mesh = rectangle_mesh(size, (Vec3::X, Vec3::Y), Vec3::Z);
+        let rendering = ClothRendering::init(&mesh, Default::default()).unwrap();
+        quad_cloth(&rendering.vertex_positions, &rendering.indices)
+    }
 
     mod init_from_mesh {
         use super::*;
-        use crate::components::cloth_rendering::ClothRendering;
         use bevy::transform::prelude::Transform;
 
         fn expected_stick_len(
@@ -340,6 +1906,16 @@ mod tests {
                         (size_x - 1) * size_y + (size_y - 1) * size_x + (size_x - 1) * (size_y - 1)
                     );
                 }
+                StickGeneration::MassSpring => {
+                    assert_eq!(
+                        len,
+                        (size_x - 1) * size_y
+                            + (size_y - 1) * size_x
+                            + 2 * (size_x - 1) * (size_y - 1)
+                            + (size_x - 2) * size_y
+                            + (size_y - 2) * size_x
+                    );
+                }
             }
         }
 
@@ -354,16 +1930,12 @@ mod tests {
                 Default::default(),
                 StickGeneration::Quads,
                 StickLen::Auto,
-                StickMode::Fixed,
+                StickMode::default(),
                 &matrix,
             );
             assert_eq!(cloth.current_point_positions.len(), 100 * 100);
             assert_eq!(cloth.previous_point_positions.len(), 100 * 100);
-            expected_stick_len(
-                cloth.stick_lengths.len(),
-                StickGeneration::Quads,
-                (100, 100),
-            );
+            expected_stick_len(cloth.sticks().len(), StickGeneration::Quads, (100, 100));
         }
 
         #[test]
@@ -377,12 +1949,38 @@ mod tests {
                 Default::default(),
                 StickGeneration::Quads,
                 StickLen::Auto,
-                StickMode::Fixed,
+                StickMode::default(),
                 &matrix,
             );
             assert_eq!(cloth.current_point_positions.len(), 66 * 42);
             assert_eq!(cloth.previous_point_positions.len(), 66 * 42);
-            expected_stick_len(cloth.stick_lengths.len(), StickGeneration::Quads, (66, 42));
+            expected_stick_len(cloth.sticks().len(), StickGeneration::Quads, (66, 42));
+        }
+
+        #[test]
+        fn generates_independent_stick_batches() {
+            let mesh = rectangle_mesh((20, 20), (Vec3::X, -Vec3::Y), Vec3::Z);
+            let matrix = Transform::default().compute_matrix();
+            let cloth_rendering = ClothRendering::init(&mesh, Default::default()).unwrap();
+            let cloth = Cloth::new(
+                &cloth_rendering.vertex_positions,
+                &cloth_rendering.indices,
+                Default::default(),
+                StickGeneration::MassSpring,
+                StickLen::Auto,
+                StickMode::default(),
+                &matrix,
+            );
+            let mut batched = 0;
+            for batch in cloth.stick_batches() {
+                let points: HashSet<_> = batch
+                    .iter()
+                    .flat_map(|i| cloth.sticks().ids()[*i])
+                    .collect();
+                assert_eq!(points.len(), batch.len() * 2);
+                batched += batch.len();
+            }
+            assert_eq!(batched, cloth.sticks().len());
         }
 
         #[test]
@@ -396,16 +1994,12 @@ mod tests {
                 Default::default(),
                 StickGeneration::Triangles,
                 StickLen::Auto,
-                StickMode::Fixed,
+                StickMode::default(),
                 &matrix,
             );
             assert_eq!(cloth.current_point_positions.len(), 100 * 100);
             assert_eq!(cloth.previous_point_positions.len(), 100 * 100);
-            expected_stick_len(
-                cloth.stick_lengths.len(),
-                StickGeneration::Triangles,
-                (100, 100),
-            );
+            expected_stick_len(cloth.sticks().len(), StickGeneration::Triangles, (100, 100));
         }
 
         #[test]
@@ -419,16 +2013,455 @@ mod tests {
                 Default::default(),
                 StickGeneration::Triangles,
                 StickLen::Auto,
-                StickMode::Fixed,
+                StickMode::default(),
                 &matrix,
             );
             assert_eq!(cloth.current_point_positions.len(), 66 * 42);
             assert_eq!(cloth.previous_point_positions.len(), 66 * 42);
-            expected_stick_len(
-                cloth.stick_lengths.len(),
-                StickGeneration::Triangles,
-                (66, 42),
+            expected_stick_len(cloth.sticks().len(), StickGeneration::Triangles, (66, 42));
+        }
+
+        #[test]
+        fn works_with_mass_springs() {
+            let mesh = rectangle_mesh((66, 42), (Vec3::X, -Vec3::Y), Vec3::Z);
+            let matrix = Transform::default().compute_matrix();
+            let cloth_rendering = ClothRendering::init(&mesh, Default::default()).unwrap();
+            let cloth = Cloth::new(
+                &cloth_rendering.vertex_positions,
+                &cloth_rendering.indices,
+                Default::default(),
+                StickGeneration::MassSpring,
+                StickLen::Auto,
+                StickMode::default(),
+                &matrix,
+            );
+            expected_stick_len(cloth.sticks().len(), StickGeneration::MassSpring, (66, 42));
+            let shear_count = cloth
+                .sticks()
+                .categories()
+                .iter()
+                .filter(|c| **c == StickCategory::Shear)
+                .count();
+            assert_eq!(shear_count, 2 * 65 * 41);
+        }
+    }
+
+    mod xpbd {
+        use super::*;
+
+        fn stick_cloth(stick_mode: StickMode) -> Cloth {
+            let mut cloth = anchored_cloth(
+                &[Vec3::ZERO, Vec3::X],
+                &[],
+                HashMap::from([(0, VertexAnchor::default())]),
             );
+            cloth.add_stick([0, 1], 1.0, stick_mode, StickCategory::Structural);
+            cloth
+        }
+
+        #[test]
+        fn rigid_stick_reaches_target_length() {
+            let mut cloth = stick_cloth(StickMode::default());
+            cloth.current_point_positions[1] = Vec3A::X * 2.0;
+            cloth.update_sticks_xpbd(1, 1.0 / 60.0, &TaskPool::new());
+            let len = cloth.current_point_positions[0].distance(cloth.current_point_positions[1]);
+            assert!((len - 1.0).abs() < 1e-5);
         }
+
+        #[test]
+        fn compliant_stick_is_depth_independent() {
+            let compute = |depth| {
+                let mut cloth = stick_cloth(StickMode::Compliant {
+                    compliance: 1e-3,
+                    tear_threshold: None,
+                });
+                cloth.current_point_positions[1] = Vec3A::X * 2.0;
+                cloth.update_sticks_xpbd(depth, 1.0 / 60.0, &TaskPool::new());
+                cloth.current_point_positions[1].x
+            };
+            let (shallow, deep) = (compute(1), compute(20));
+            assert!(shallow > 1.0 && shallow < 2.0);
+            assert!((shallow - deep).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn tearing_splits_topology() {
+        let mut cloth = quad_cloth(
+            &[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::ONE],
+            &[0, 1, 2, 1, 3, 2],
+        );
+        cloth.add_stick([1, 2], 1.0, StickMode::default(), StickCategory::Shear);
+        assert!(cloth.tear_stick([2, 1]));
+        assert!(!cloth.tear_stick([1, 2]));
+        assert_eq!(cloth.current_point_positions.len(), 6);
+        assert_eq!(cloth.triangles, vec![[0, 1, 2], [5, 3, 4]]);
+        let tears = cloth.drain_tears();
+        assert_eq!(tears.len(), 1);
+        assert_eq!(tears[0].splits.len(), 2);
+        assert!(cloth.drain_tears().is_empty());
+    }
+
+    #[test]
+    fn sticks_tear_above_their_mode_threshold() {
+        let mut cloth = quad_cloth(&[Vec3::ZERO, Vec3::X * 2.0, Vec3::X * 3.0], &[]);
+        let tearable = StickMode::Fixed {
+            tear_threshold: Some(1.5),
+        };
+        cloth.add_stick([0, 1], 1.0, tearable, StickCategory::Structural);
+        cloth.add_stick([1, 2], 1.0, tearable, StickCategory::Structural);
+        assert!(cloth.is_tearable());
+        assert_eq!(cloth.tear_overstretched_sticks(), 1);
+        assert!(!cloth.sticks().contains([0, 1]));
+        assert!(cloth.sticks().contains([1, 2]));
+    }
+
+    #[test]
+    fn tearing_follows_split_sticks() {
+        let mesh = rectangle_mesh((3, 3), (Vec3::X, Vec3::Y), Vec3::Z);
+        let rendering = ClothRendering::init(&mesh, Default::default()).unwrap();
+        let mut cloth = Cloth::new(
+            &rendering.vertex_positions,
+            &rendering.indices,
+            HashMap::default(),
+            StickGeneration::Triangles,
+            StickLen::Auto,
+            StickMode::Fixed {
+                tear_threshold: Some(1.5),
+            },
+            &Mat4::IDENTITY,
+        );
+        let overstretched = |cloth: &Cloth| {
+            let sticks = cloth.sticks();
+            (0..sticks.len())
+                .filter(|i| {
+                    let [a, b] = sticks.ids()[*i];
+                    cloth.current_point_positions[a].distance(cloth.current_point_positions[b])
+                        > sticks.lengths()[*i] * 1.5
+                })
+                .count()
+        };
+        // The sticks of the center point share an endpoint
+        cloth.current_point_positions[4] += Vec3A::Z * 3.0;
+        let count = overstretched(&cloth);
+        assert!(count > 1);
+        assert_eq!(cloth.tear_overstretched_sticks(), count);
+        assert_eq!(overstretched(&cloth), 0);
+    }
+
+    #[test]
+    fn jacobi_is_order_independent() {
+        let positions = [Vec3::ZERO, Vec3::X * 2.0, Vec3::X * 4.0];
+        let compute = |sticks: [StickId; 2]| {
+            let mut cloth = quad_cloth(&positions, &[]);
+            for id in sticks {
+                cloth.add_stick(id, 1.0, StickMode::default(), StickCategory::Structural);
+            }
+            cloth.update_sticks_jacobi(1, &TaskPool::new());
+            cloth.current_point_positions
+        };
+        let result = compute([[0, 1], [1, 2]]);
+        assert_eq!(result, compute([[1, 2], [0, 1]]));
+        assert_eq!(result[1], Vec3A::X * 2.0);
+    }
+
+    #[test]
+    fn batches_solve_independently_of_threads() {
+        let compute = |threads: usize| {
+            let task_pool = TaskPoolBuilder::new().num_threads(threads).build();
+            let mut cloth = rectangle_cloth((40, 40));
+            assert!(cloth
+                .stick_batches()
+                .iter()
+                .any(|b| b.len() > STICK_CHUNK_SIZE));
+            for (point, offset) in cloth
+                .current_point_positions
+                .iter_mut()
+                .zip([0.0, 0.03, 0.01, 0.05, 0.02].into_iter().cycle())
+            {
+                point.z += offset;
+            }
+            cloth.update_sticks(2, &task_pool);
+            cloth.update_sticks_xpbd(2, 1.0 / 60.0, &task_pool);
+            cloth.current_point_positions
+        };
+        assert_eq!(compute(1), compute(4));
+    }
+
+    #[test]
+    fn heavy_points_move_less() {
+        let mut cloth = quad_cloth(&[Vec3::ZERO, Vec3::X * 2.0], &[]);
+        cloth.add_stick([0, 1], 1.0, StickMode::default(), StickCategory::Structural);
+        cloth.add_weight(&[0], 2.0);
+        cloth.update_sticks(1, &TaskPool::new());
+        assert!((cloth.current_point_positions[0].x - 0.25).abs() < 1e-5);
+        assert!((cloth.current_point_positions[1].x - 1.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn self_collision_ignores_connected_points() {
+        let mut cloth = quad_cloth(&[Vec3::ZERO, Vec3::X * 0.1, Vec3::Y * 0.1], &[]);
+        cloth.add_stick([0, 1], 0.1, StickMode::default(), StickCategory::Structural);
+        cloth.add_stick([2, 1], 0.1, StickMode::default(), StickCategory::Structural);
+        cloth.set_self_collision(0.5);
+        cloth.update_self_collisions();
+        assert_eq!(cloth.current_point_positions[1], Vec3A::X * 0.1);
+        assert!(cloth.current_point_positions[2].distance(Vec3A::ZERO) > 0.1);
+    }
+
+    #[test]
+    fn identical_simulations_have_identical_state_hash() {
+        let simulate = |gravity: Vec3| {
+            let mut cloth = rectangle_cloth((10, 10));
+            cloth.pin_point(0, VertexAnchor::default(), &GlobalTransform::IDENTITY);
+            for _ in 0..10 {
+                cloth.begin_fixed_step();
+                cloth.update_points(0.98, gravity);
+                cloth.update_sticks(5, &TaskPool::new());
+            }
+            cloth.state_hash()
+        };
+        let gravity = Vec3::NEG_Y * 0.01;
+        assert_eq!(simulate(gravity), simulate(gravity));
+        assert_ne!(simulate(gravity), simulate(gravity * 2.0));
+    }
+
+    #[test]
+    fn cloth_sleeps_at_rest_and_wakes_up_on_impulse() {
+        let mut cloth = anchored_cloth(
+            &[Vec3::ZERO, Vec3::X],
+            &[],
+            HashMap::from([(0, VertexAnchor::default())]),
+        );
+        let step = |cloth: &mut Cloth| {
+            cloth.update_points(1.0, Vec3::ZERO);
+            cloth.update_sleep(0.001, 10);
+        };
+        for _ in 0..9 {
+            step(&mut cloth);
+        }
+        assert!(!cloth.is_sleeping());
+        step(&mut cloth);
+        assert!(cloth.is_sleeping());
+        cloth.apply_impulse(&[1], Vec3::Y);
+        assert!(!cloth.is_sleeping());
+        step(&mut cloth);
+        assert_eq!(cloth.rest_steps, 0);
+        assert_eq!(cloth.current_point_positions[1], Vec3A::X + Vec3A::Y);
+    }
+
+    #[test]
+    fn swept_collisions_stop_tunneling_points() {
+        let mut cloth = quad_cloth(&[Vec3::ZERO, Vec3::X], &[]);
+        cloth.current_point_positions[0] = Vec3A::Z * 2.0;
+        // Thin wall on the `z = 1.0` plane
+        cloth.solve_swept_collisions(|start, current| {
+            (start.z < 1.0 && current.z > 1.0).then(|| Vec3::new(current.x, current.y, 0.9))
+        });
+        assert_eq!(cloth.current_point_positions[0], Vec3A::Z * 0.9);
+        assert_eq!(cloth.current_point_positions[1], Vec3A::X);
+    }
+
+    #[test]
+    fn swept_collisions_cover_every_substep() {
+        let mut cloth = quad_cloth(&[Vec3::ZERO, Vec3::X], &[]);
+        cloth.begin_frame();
+        cloth.begin_fixed_step();
+        // The wall is crossed during the first substep
+        cloth.previous_point_positions[0] = Vec3A::Z * -0.8;
+        for _ in 0..4 {
+            cloth.update_points(1.0, Vec3::ZERO);
+        }
+        // The last substep started behind the wall
+        assert!(cloth.previous_point_positions[0].z > 1.0);
+        cloth.solve_swept_collisions(|start, current| {
+            (start.z < 1.0 && current.z > 1.0).then(|| Vec3::new(current.x, current.y, 0.9))
+        });
+        assert_eq!(cloth.current_point_positions[0], Vec3A::Z * 0.9);
+    }
+
+    #[test]
+    fn rebuilds_keep_runtime_edits() {
+        let positions = [Vec3::ZERO, Vec3::X, Vec3::X * 2.0];
+        let anchors = || HashMap::from([(0, VertexAnchor::default())]);
+        let mut cloth = anchored_cloth(&positions, &[], anchors());
+        cloth.set_point_mass(2, 4.0);
+        cloth.clear_edits();
+        assert!(cloth.unpin_point(0).is_some());
+        assert!(cloth.pin_point(1, VertexAnchor::default(), &GlobalTransform::IDENTITY));
+        cloth.set_point_mass(2, 2.0);
+        // Edits are kept through successive rebuilds
+        for _ in 0..2 {
+            let mut rebuilt = anchored_cloth(&positions, &[], anchors());
+            assert!(rebuilt.copy_state_from(&cloth));
+            assert!(!rebuilt.is_pinned(0));
+            assert!(rebuilt.is_pinned(1));
+            assert_eq!(rebuilt.point_mass(2), Some(2.0));
+            cloth = rebuilt;
+        }
+        // Incompatible topologies are not copied
+        let mut rebuilt = anchored_cloth(&positions[..2], &[], anchors());
+        assert!(!rebuilt.copy_state_from(&cloth));
+        assert!(rebuilt.is_pinned(0));
+    }
+
+    #[test]
+    fn inverse_masses_follow_anchor_and_mass_edits() {
+        let soft_anchor = VertexAnchor {
+            compliance: Some(1.0),
+            ..Default::default()
+        };
+        let mut cloth = anchored_cloth(
+            &[Vec3::ZERO, Vec3::X, Vec3::Y],
+            &[],
+            HashMap::from([(0, VertexAnchor::default()), (1, soft_anchor)]),
+        );
+        let inverse_masses =
+            |cloth: &Cloth| Vec4::from([0, 1, 2, 3].map(|i| cloth.inverse_mass(i)));
+        assert_eq!(inverse_masses(&cloth), Vec4::new(0.0, 1.0, 1.0, 1.0));
+        cloth.set_point_mass(0, 2.0);
+        cloth.set_point_mass(1, 2.0);
+        assert_eq!(inverse_masses(&cloth), Vec4::new(0.0, 0.5, 1.0, 1.0));
+        assert!(cloth.unpin_point(0).is_some());
+        assert!(cloth.pin_point(2, VertexAnchor::default(), &GlobalTransform::IDENTITY));
+        assert_eq!(cloth.duplicate_point(2), 3);
+        assert_eq!(inverse_masses(&cloth), Vec4::new(0.5, 0.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn unpinned_points_keep_their_velocity() {
+        let mut cloth = anchored_cloth(
+            &[Vec3::ZERO, Vec3::X],
+            &[],
+            HashMap::from([(0, VertexAnchor::default())]),
+        );
+        let transform = GlobalTransform::from_translation(Vec3::Y);
+        assert!(cloth.pin_point(1, VertexAnchor::default(), &transform));
+        assert_eq!(cloth.anchored_points[&1].1, Vec3::X - Vec3::Y);
+        // The cloth moved up, dragging the anchored points
+        cloth.update_points(1.0, Vec3::ZERO);
+        cloth.update_anchored_points(&transform, |_| None, 1.0);
+        assert!(cloth.unpin_point(0).is_some());
+        assert!(cloth.unpin_point(0).is_none());
+        cloth.update_points(1.0, Vec3::ZERO);
+        assert_eq!(cloth.current_point_positions[0], Vec3A::Y * 2.0);
+        assert_eq!(cloth.current_point_positions[1], Vec3A::X);
+        let changes = cloth.drain_anchor_changes();
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].previous.is_none());
+        assert!(changes[1].anchor.is_none());
+    }
+
+    #[test]
+    fn soft_anchors_pull_points() {
+        let soft_anchor = VertexAnchor {
+            compliance: Some(1.0),
+            ..Default::default()
+        };
+        let mut cloth = anchored_cloth(
+            &[Vec3::ZERO, Vec3::X],
+            &[],
+            HashMap::from([(0, VertexAnchor::default()), (1, soft_anchor)]),
+        );
+        assert!(cloth.is_pinned(0));
+        assert!(!cloth.is_pinned(1));
+        assert!(cloth.inverse_mass(1) > 0.0);
+        let transform = GlobalTransform::from_translation(Vec3::Y);
+        cloth.update_anchored_points(&transform, |_| None, 1.0);
+        assert_eq!(cloth.current_point_positions[0], Vec3A::Y);
+        assert_eq!(cloth.current_point_positions[1], Vec3A::X + Vec3A::Y * 0.5);
+    }
+
+    #[test]
+    fn soft_anchors_settle_independently_of_substeps() {
+        let compliance = 0.01;
+        let gravity = Vec3::NEG_Y * 9.81;
+        let settle = |substeps: u8| {
+            let soft_anchor = VertexAnchor {
+                compliance: Some(compliance),
+                ..Default::default()
+            };
+            let mut cloth = anchored_cloth(&[Vec3::ZERO], &[], HashMap::from([(0, soft_anchor)]));
+            let delta_time = 1.0 / 60.0 / f32::from(substeps);
+            for _ in 0..600 * u32::from(substeps) {
+                cloth.update_points(0.9, gravity * delta_time * delta_time);
+                cloth.update_anchored_points(&GlobalTransform::IDENTITY, |_| None, delta_time);
+            }
+            cloth.current_point_positions[0].y
+        };
+        let expected = gravity.y * compliance;
+        for substeps in [1, 4, 10] {
+            assert!((settle(substeps) - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn overstretched_anchors_break() {
+        let breakable_anchor = VertexAnchor {
+            break_threshold: Some(0.5),
+            ..Default::default()
+        };
+        let mut cloth = anchored_cloth(
+            &[Vec3::ZERO, Vec3::X, Vec3::X * 2.0],
+            &[],
+            HashMap::from([(0, breakable_anchor), (2, breakable_anchor)]),
+        );
+        cloth.add_stick([0, 1], 1.0, StickMode::default(), StickCategory::default());
+        cloth.add_stick([1, 2], 1.0, StickMode::default(), StickCategory::default());
+        cloth.current_point_positions[1] = Vec3A::new(1.0, 0.0, 0.5);
+        assert_eq!(cloth.break_overstretched_anchors(), 0);
+        cloth.current_point_positions[1] = Vec3A::new(2.0, 0.0, 1.0);
+        assert_eq!(cloth.break_overstretched_anchors(), 1);
+        assert!(cloth.anchored_points.contains_key(&2));
+        assert_eq!(cloth.drain_anchor_breaks()[0].0, 0);
+    }
+
+    #[test]
+    fn point_strains() {
+        let mut cloth = rectangle_cloth((3, 3));
+        assert!(cloth.compute_point_strains().iter().all(|s| s.abs() < 1e-5));
+        for position in &mut cloth.current_point_positions {
+            *position *= 2.0;
+        }
+        assert!(cloth
+            .compute_point_strains()
+            .iter()
+            .all(|s| (s - 1.0).abs() < 1e-5));
+        for position in &mut cloth.current_point_positions {
+            *position *= 0.25;
+        }
+        assert!(cloth
+            .compute_point_strains()
+            .iter()
+            .all(|s| (s + 0.5).abs() < 1e-5));
+    }
+
+    #[test]
+    fn ray_and_closest_point_queries() {
+        let cloth = rectangle_cloth((3, 3));
+        let ray = Ray {
+            origin: Vec3::new(0.25, 0.5, 5.0),
+            direction: Vec3::NEG_Z,
+        };
+        let hit = cloth.cast_ray(ray).unwrap();
+        assert_eq!(hit.point, Vec3::new(0.25, 0.5, 0.0));
+        assert_eq!(hit.normal, Vec3::Z);
+        let [a, b, c] = cloth.triangle_positions(cloth.triangles[hit.triangle]);
+        let point = a * hit.barycentric.x + b * hit.barycentric.y + c * hit.barycentric.z;
+        assert!(point.distance(Vec3A::from(hit.point)) < 1e-5);
+        let projection = cloth.closest_point(Vec3::new(0.5, 0.5, 2.0)).unwrap();
+        assert!(projection.point.distance(Vec3::new(0.5, 0.5, 0.0)) < 1e-5);
+        assert!((projection.distance - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn interpolated_vertex_positions() {
+        let mut cloth = quad_cloth(&[Vec3::ZERO, Vec3::X], &[]);
+        cloth.begin_fixed_step();
+        cloth.current_point_positions = vec![Vec3A::Y, Vec3A::X + Vec3A::Y];
+        let positions: Vec<_> = cloth
+            .compute_interpolated_vertex_positions(&GlobalTransform::default(), 0.5)
+            .collect();
+        assert_eq!(positions, vec![Vec3::Y * 0.5, Vec3::X + Vec3::Y * 0.5]);
     }
 }
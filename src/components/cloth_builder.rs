@@ -34,6 +34,11 @@ pub struct ClothBuilder {
     /// by physics and following the attached `GlobalTransform`
     #[reflect(ignore)]
     pub anchored_position_conditions: Vec<(Arc<PinnedPosCondition>, VertexAnchor)>,
+    /// Custom cloth vertex masses, every other vertex has a mass of `1.0`
+    ///
+    /// * key: the vertex id
+    /// * value: the vertex mass
+    pub vertex_masses: HashMap<usize, f32>,
     /// How cloth sticks get generated
     pub stick_generation: StickGeneration,
     /// Define cloth sticks target length
@@ -42,6 +47,41 @@ pub struct ClothBuilder {
     pub normals_computing: NormalComputing,
     /// Default behaviour for cloth sticks
     pub default_stick_mode: StickMode,
+    /// Custom behaviour for cloth sticks of a given category, overriding
+    /// [`Self::default_stick_mode`]
+    pub category_stick_modes: HashMap<StickCategory, StickMode>,
+    /// Custom target length for cloth sticks of a given category, overriding
+    /// [`Self::stick_length`]
+    pub category_stick_lengths: HashMap<StickCategory, StickLen>,
+    /// Cloth bending stiffness, between 0.0 and 1.0. If set to 0.0, no
+    /// bending constraints are generated
+    pub bending_stiffness: f32,
+    /// Defines the rest angle of cloth bending constraints
+    pub bending_angle: BendingAngle,
+    /// Optional maximum stretch ratio of the cloth long range attachments
+    /// (tethers). If set, every free vertex can't go further from its nearest
+    /// anchored vertex than its rest geodesic distance multiplied by this
+    /// value.
+    pub tether_stretch: Option<f32>,
+    /// Optional target volume ratio for closed meshes. If set, the cloth will
+    /// attempt to keep its enclosed volume at this ratio of its initial volume
+    pub volume_target_ratio: Option<f32>,
+    /// Volume preservation pressure coefficient, between 0.0 and 1.0. Only
+    /// used if [`Self::volume_target_ratio`] is set
+    pub volume_pressure: f32,
+    /// Optional self collision thickness, the minimum distance between two
+    /// unconnected cloth points. If not set, the cloth can pass through itself
+    pub self_collision_thickness: Option<f32>,
+    /// If set to true, the per-vertex strain will be exported in the mesh as
+    /// the [`ClothRendering::ATTRIBUTE_STRAIN`] custom attribute
+    ///
+    /// [`ClothRendering::ATTRIBUTE_STRAIN`]: crate::components::cloth_rendering::ClothRendering::ATTRIBUTE_STRAIN
+    pub strain_attribute: bool,
+    /// If set to true, the per-vertex velocity will be exported in the mesh as
+    /// the [`ClothRendering::ATTRIBUTE_VELOCITY`] custom attribute
+    ///
+    /// [`ClothRendering::ATTRIBUTE_VELOCITY`]: crate::components::cloth_rendering::ClothRendering::ATTRIBUTE_VELOCITY
+    pub velocity_attribute: bool,
 }
 
 #[allow(clippy::missing_const_for_fn)]
@@ -194,8 +234,7 @@ impl ClothBuilder {
     /// # Arguments
     ///
     /// * `condition` - a function determining if a given position ([`Vec3`]) is
-    ///   pinned to the
-    /// associated `GlobalTransform`.
+    ///   pinned to the associated `GlobalTransform`.
     ///
     /// # Example
     ///
@@ -236,6 +275,31 @@ impl ClothBuilder {
         self
     }
 
+    /// Sets the mass of the vertex with the given id, the default mass being
+    /// `1.0`. Heavier vertices are less moved by the cloth constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_id` - the vertex id
+    /// * `mass` - the vertex mass, must be positive
+    #[inline]
+    pub fn with_vertex_mass(mut self, vertex_id: usize, mass: f32) -> Self {
+        self.vertex_masses.insert(vertex_id, mass);
+        self
+    }
+
+    /// Sets the mass of the vertices with the given ids, the default mass
+    /// being `1.0`. Heavier vertices are less moved by the cloth constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_masses` - Iterator on the vertex ids and their mass
+    #[inline]
+    pub fn with_vertex_masses(mut self, vertex_masses: impl Iterator<Item = (usize, f32)>) -> Self {
+        self.vertex_masses.extend(vertex_masses);
+        self
+    }
+
     /// Sets the stick generation option for the cloth
     ///
     /// # Arguments
@@ -269,6 +333,106 @@ impl ClothBuilder {
         self
     }
 
+    /// Sets the stick mode option for the cloth sticks of a given category,
+    /// overriding the default stick mode
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - The category of the sticks to customize
+    /// * `stick_mode` - Cloth sticks behaviour for this category
+    #[inline]
+    pub fn with_category_stick_mode(
+        mut self,
+        category: StickCategory,
+        stick_mode: StickMode,
+    ) -> Self {
+        self.category_stick_modes.insert(category, stick_mode);
+        self
+    }
+
+    /// Sets the sticks target length option for the cloth sticks of a given
+    /// category, overriding the default stick length
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - The category of the sticks to customize
+    /// * `stick_len` - Cloth sticks target length option for this category
+    #[inline]
+    pub fn with_category_stick_length(
+        mut self,
+        category: StickCategory,
+        stick_len: StickLen,
+    ) -> Self {
+        self.category_stick_lengths.insert(category, stick_len);
+        self
+    }
+
+    /// Sets the bending stiffness of the cloth, generating bending constraints
+    /// between every pair of adjacent triangles
+    ///
+    /// # Arguments
+    ///
+    /// * `stiffness` - Bending stiffness, between 0.0 (no bending resistance)
+    ///   and 1.0
+    #[inline]
+    pub fn with_bending_stiffness(mut self, stiffness: f32) -> Self {
+        self.bending_stiffness = stiffness;
+        self
+    }
+
+    /// Sets the bending constraints rest angle option for the cloth
+    ///
+    /// # Arguments
+    ///
+    /// * `bending_angle` - Cloth bending rest angle option
+    #[inline]
+    pub fn with_bending_angle(mut self, bending_angle: BendingAngle) -> Self {
+        self.bending_angle = bending_angle;
+        self
+    }
+
+    /// Enables long range attachments (tethers) for the cloth, preventing free
+    /// vertices from overstretching away from their nearest anchored vertex.
+    /// Useful for tall cloths anchored only on one side, like capes.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_stretch` - Maximum stretch ratio of the rest geodesic distance
+    ///   between a vertex and its nearest anchor (`1.0` means no stretch)
+    #[inline]
+    pub fn with_tethers(mut self, max_stretch: f32) -> Self {
+        self.tether_stretch = Some(max_stretch);
+        self
+    }
+
+    /// Enables volume preservation for closed meshes, applying an internal
+    /// pressure to the cloth. Useful for balloons, airbags or bouncy castles.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_ratio` - Target volume ratio of the initial enclosed volume
+    ///   (`1.0` keeps the initial volume)
+    /// * `pressure` - Pressure coefficient, between 0.0 and 1.0
+    #[inline]
+    pub fn with_volume_preservation(mut self, target_ratio: f32, pressure: f32) -> Self {
+        self.volume_target_ratio = Some(target_ratio);
+        self.volume_pressure = pressure;
+        self
+    }
+
+    /// Enables cloth self collision, preventing the cloth from passing
+    /// through itself. Useful for folded or draped cloths.
+    ///
+    /// # Arguments
+    ///
+    /// * `thickness` - The minimum distance between two unconnected cloth
+    ///   points
+    #[inline]
+    pub fn with_self_collision(mut self, thickness: f32) -> Self {
+        self.self_collision_thickness = Some(thickness);
+        self
+    }
+
     /// The cloth won't re-compute the mesh normals. It's the fastest option but
     /// lighting will become inconsistent
     #[inline]
@@ -309,6 +473,22 @@ impl ClothBuilder {
         self
     }
 
+    /// The cloth will export the per-vertex strain as a custom mesh attribute
+    /// (See [`Self::strain_attribute`])
+    #[inline]
+    pub fn with_strain_attribute(mut self) -> Self {
+        self.strain_attribute = true;
+        self
+    }
+
+    /// The cloth will export the per-vertex velocity as a custom mesh attribute
+    /// (See [`Self::velocity_attribute`])
+    #[inline]
+    pub fn with_velocity_attribute(mut self) -> Self {
+        self.velocity_attribute = true;
+        self
+    }
+
     /// Retrieves all anchored vertex ids using:
     /// - [`Self::anchored_vertex_ids`] explicit ids
     /// - [`Self::anchored_vertex_colors`] to find every vertex id in `mesh`
@@ -1,12 +1,14 @@
-use crate::Error;
+use crate::{tear::PointSplit, Error};
 use bevy::{
     ecs::prelude::Component,
+    log,
     math::Vec3,
     reflect::Reflect,
     render::{
         color::Color,
-        mesh::{Indices, Mesh, VertexAttributeValues},
+        mesh::{Indices, Mesh, MeshVertexAttribute, VertexAttributeValues},
         primitives::Aabb,
+        render_resource::VertexFormat,
     },
     utils::HashMap,
 };
@@ -34,14 +36,47 @@ pub struct ClothRendering {
     pub vertex_uvs: Option<Vec<[f32; 2]>>,
     /// Mesh vertex colors
     pub vertex_colors: Option<Vec<[f32; 4]>>,
+    /// Original mesh vertex normals, only used with [`NormalComputing::None`]
+    pub vertex_normals: Option<Vec<[f32; 3]>>,
+    /// Optional mesh vertex strains, exported as
+    /// [`Self::ATTRIBUTE_STRAIN`] (See [`Cloth::compute_point_strains`])
+    ///
+    /// [`Cloth::compute_point_strains`]: crate::components::cloth::Cloth::compute_point_strains
+    pub vertex_strains: Option<Vec<f32>>,
+    /// Optional local space mesh vertex velocities, exported as
+    /// [`Self::ATTRIBUTE_VELOCITY`]
+    pub vertex_velocities: Option<Vec<Vec3>>,
     /// Mesh vertex indices
     pub indices: Vec<u32>,
     /// If set to true, the vertices will be duplicated and normals computed
     /// before updating the mesh
     pub normal_computing: NormalComputing,
+    /// Cloth point ids of the vertices duplicated by tearing, appended after
+    /// the original mesh vertices
+    pub duplicated_points: Vec<usize>,
+    /// Set when the mesh was last updated from a sleeping cloth, the mesh is
+    /// then no longer updated until the cloth wakes up
+    pub sleeping: bool,
+    /// Original mesh data the cloth was built from, used to rebuild the cloth
+    /// when its `ClothBuilder` changes. Only the indices and the position, UV,
+    /// color and normal attributes are kept.
+    pub source_mesh: Option<Mesh>,
+    /// Set when vertices were split since the last [`Self::mark_applied`], the
+    /// mesh UVs, colors, normals and indices are then rewritten by
+    /// [`Self::apply`]
+    pub(crate) topology_changed: bool,
 }
 
 impl ClothRendering {
+    /// Custom vertex attribute holding the per-vertex cloth strain, enabled
+    /// with `ClothBuilder::with_strain_attribute`
+    pub const ATTRIBUTE_STRAIN: MeshVertexAttribute =
+        MeshVertexAttribute::new("Vertex_Strain", 2_069_147_512, VertexFormat::Float32);
+    /// Custom vertex attribute holding the per-vertex local space cloth
+    /// velocity, enabled with `ClothBuilder::with_velocity_attribute`
+    pub const ATTRIBUTE_VELOCITY: MeshVertexAttribute =
+        MeshVertexAttribute::new("Vertex_Velocity", 2_069_147_513, VertexFormat::Float32x3);
+
     fn face_normal(a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
         (b - a).cross(c - a).normalize() // TODO: enable default value
     }
@@ -56,7 +91,7 @@ impl ClothRendering {
     ///
     /// The function fails in the event of the mesh `ATTRIBUTE_POSITION`
     /// attribute is missing or invalid. It may also fail if the mesh
-    /// doesn't have indices.
+    /// doesn't have indices or if an index is out of bounds.
     pub fn init(mesh: &Mesh, normal_computing: NormalComputing) -> Result<Self, Error> {
         let vertex_positions = mesh
             .attribute(Mesh::ATTRIBUTE_POSITION)
@@ -107,6 +142,22 @@ impl ClothRendering {
             });
         }
 
+        // Normals
+        let vertex_normals = mesh
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .and_then(|attr| match attr {
+                VertexAttributeValues::Float32x3(v) => Some(v.clone()),
+                _ => None,
+            });
+        // Assertion
+        let attr_count = vertex_normals.as_ref().map_or(vertex_count, Vec::len);
+        if attr_count != vertex_count {
+            return Err(Error::InvalidMeshAttribute {
+                attribute: "Vertex_Normal".to_string(),
+                message: format!("Expected {vertex_count} values, got {attr_count}"),
+            });
+        }
+
         let indices = match mesh.indices() {
             None => return Err(Error::MissingIndices),
             Some(i) => match i {
@@ -114,12 +165,25 @@ impl ClothRendering {
                 Indices::U32(v) => v.clone(),
             },
         };
+        if let Some(index) = indices.iter().find(|i| **i as usize >= vertex_count) {
+            return Err(Error::IndexOutOfBounds {
+                index: *index,
+                vertex_count,
+            });
+        }
         Ok(Self {
             vertex_positions,
             vertex_uvs,
             vertex_colors,
+            vertex_normals,
+            vertex_strains: None,
+            vertex_velocities: None,
             indices,
             normal_computing,
+            duplicated_points: Vec::new(),
+            sleeping: false,
+            source_mesh: None,
+            topology_changed: false,
         })
     }
 
@@ -133,8 +197,8 @@ impl ClothRendering {
     /// - 1: The half extents of the bounding box
     #[must_use]
     pub fn compute_aabb(&self) -> Aabb {
-        const VEC3_MIN: Vec3 = Vec3::from_array([std::f32::MIN; 3]);
-        const VEC3_MAX: Vec3 = Vec3::from_array([std::f32::MAX; 3]);
+        const VEC3_MIN: Vec3 = Vec3::from_array([f32::MIN; 3]);
+        const VEC3_MAX: Vec3 = Vec3::from_array([f32::MAX; 3]);
 
         let mut minimum = VEC3_MAX;
         let mut maximum = VEC3_MIN;
@@ -153,7 +217,100 @@ impl ClothRendering {
     /// previous vertices
     pub fn update_positions(&mut self, vertex_positions: impl ExactSizeIterator<Item = Vec3>) {
         debug_assert!(vertex_positions.len() >= self.vertex_positions.len());
-        self.vertex_positions = vertex_positions.take(self.vertex_positions.len()).collect();
+        if self.duplicated_points.is_empty() {
+            self.vertex_positions = vertex_positions.take(self.vertex_positions.len()).collect();
+            return;
+        }
+        let positions: Vec<Vec3> = vertex_positions.collect();
+        let base_len = self.vertex_positions.len() - self.duplicated_points.len();
+        self.vertex_positions = positions[..base_len]
+            .iter()
+            .copied()
+            .chain(self.duplicated_points.iter().map(|i| positions[*i]))
+            .collect();
+    }
+
+    /// Maps per cloth point values to per vertex values, following the
+    /// vertices duplicated by tearing
+    fn vertex_values<T: Copy>(&self, point_values: &[T]) -> Vec<T> {
+        let base_len = self.vertex_positions.len() - self.duplicated_points.len();
+        point_values[..base_len]
+            .iter()
+            .copied()
+            .chain(self.duplicated_points.iter().map(|i| point_values[*i]))
+            .collect()
+    }
+
+    /// Updates the vertex strains from the cloth point strains, if enabled
+    /// (See [`Self::vertex_strains`])
+    pub fn update_strains(&mut self, point_strains: &[f32]) {
+        if self.vertex_strains.is_some() {
+            self.vertex_strains = Some(self.vertex_values(point_strains));
+        }
+    }
+
+    /// Updates the vertex velocities from the cloth point velocities, if
+    /// enabled (See [`Self::vertex_velocities`])
+    pub fn update_velocities(&mut self, point_velocities: impl ExactSizeIterator<Item = Vec3>) {
+        if self.vertex_velocities.is_some() {
+            let velocities: Vec<Vec3> = point_velocities.collect();
+            self.vertex_velocities = Some(self.vertex_values(&velocities));
+        }
+    }
+
+    /// Retrieves the vertex id associated with the given cloth `point`
+    fn point_vertex(&self, point: usize) -> Option<usize> {
+        let base_len = self.vertex_positions.len() - self.duplicated_points.len();
+        if point < base_len {
+            Some(point)
+        } else {
+            self.duplicated_points
+                .iter()
+                .position(|p| *p == point)
+                .map(|i| base_len + i)
+        }
+    }
+
+    /// Duplicates a vertex following a cloth tear, the triangles of the
+    /// `split` will use the new vertex.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn split_vertex(&mut self, split: &PointSplit) {
+        let Some(original) = self.point_vertex(split.original) else {
+            log::warn!(
+                "Failed to split cloth vertex, point {} has no associated vertex",
+                split.original
+            );
+            return;
+        };
+        let new = self.vertex_positions.len() as u32;
+        self.vertex_positions.push(self.vertex_positions[original]);
+        if let Some(uvs) = &mut self.vertex_uvs {
+            uvs.push(uvs[original]);
+        }
+        if let Some(colors) = &mut self.vertex_colors {
+            colors.push(colors[original]);
+        }
+        if let Some(normals) = &mut self.vertex_normals {
+            normals.push(normals[original]);
+        }
+        if let Some(strains) = &mut self.vertex_strains {
+            strains.push(strains[original]);
+        }
+        if let Some(velocities) = &mut self.vertex_velocities {
+            velocities.push(velocities[original]);
+        }
+        self.duplicated_points.push(split.new);
+        self.topology_changed = true;
+        for t in &split.triangles {
+            let Some(triangle) = self.indices.get_mut(t * 3..t * 3 + 3) else {
+                continue;
+            };
+            for i in triangle {
+                if *i == original as u32 {
+                    *i = new;
+                }
+            }
+        }
     }
 
     /// Duplicates `self` by computing one vertex position per indice.
@@ -179,8 +336,21 @@ impl ClothRendering {
             vertex_positions,
             indices,
             normal_computing: self.normal_computing,
+            duplicated_points: Vec::new(),
+            sleeping: false,
+            source_mesh: None,
+            topology_changed: false,
             vertex_uvs: vertex_uvs.into_iter().collect(),
             vertex_colors: vertex_colors.into_iter().collect(),
+            vertex_normals: None,
+            vertex_strains: self
+                .vertex_strains
+                .as_deref()
+                .map(|v| self.indices.iter().map(|i| v[*i as usize]).collect()),
+            vertex_velocities: self
+                .vertex_velocities
+                .as_deref()
+                .map(|v| self.indices.iter().map(|i| v[*i as usize]).collect()),
         }
     }
 
@@ -226,12 +396,47 @@ impl ClothRendering {
         attr.iter().map(Vec3::to_array).collect()
     }
 
+    /// Inserts the optional custom vertex attributes in the mesh (See
+    /// [`Self::ATTRIBUTE_STRAIN`] and [`Self::ATTRIBUTE_VELOCITY`])
+    fn apply_custom_attributes(&self, mesh: &mut Mesh) {
+        if let Some(ref attr) = self.vertex_strains {
+            mesh.insert_attribute(Self::ATTRIBUTE_STRAIN, attr.clone());
+        }
+        if let Some(ref attr) = self.vertex_velocities {
+            mesh.insert_attribute(Self::ATTRIBUTE_VELOCITY, Self::vec3_vertex_attr(attr));
+        }
+    }
+
+    /// Marks the rendering data as applied to the mesh, the topology is then
+    /// only rewritten by [`Self::apply`] after the next vertex split
+    pub(crate) fn mark_applied(&mut self) {
+        self.topology_changed = false;
+    }
+
     /// applies the rendering data to the mesh.
     ///
     /// If [`Self::normal_computing`] is set to
     /// [`NormalComputing::FlatNormals`], the vertices will be first be
     /// duplicated before the normals are computed
     pub fn apply(&self, mesh: &mut Mesh) {
+        if self.topology_changed && !matches!(self.normal_computing, NormalComputing::FlatNormals) {
+            // The topology was split by tearing since the last update
+            if let Some(ref attr) = self.vertex_uvs {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, attr.clone());
+            }
+            if let Some(ref attr) = self.vertex_colors {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, attr.clone());
+            }
+            if let (Some(ref attr), NormalComputing::None) =
+                (&self.vertex_normals, self.normal_computing)
+            {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, attr.clone());
+            }
+            mesh.set_indices(Some(Indices::U32(self.indices.clone())));
+        }
+        if !matches!(self.normal_computing, NormalComputing::FlatNormals) {
+            self.apply_custom_attributes(mesh);
+        }
         match self.normal_computing {
             NormalComputing::None => mesh.insert_attribute(
                 Mesh::ATTRIBUTE_POSITION,
@@ -260,6 +465,7 @@ impl ClothRendering {
                 if let Some(ref attr) = new_self.vertex_colors {
                     mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, attr.clone());
                 }
+                new_self.apply_custom_attributes(mesh);
                 let vertex_normals = new_self.compute_flat_normals();
                 mesh.insert_attribute(
                     Mesh::ATTRIBUTE_NORMAL,
@@ -270,3 +476,83 @@ impl ClothRendering {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::cloth::Cloth,
+        mesh::rectangle_mesh,
+        stick::{StickGeneration, StickLen, StickMode},
+    };
+    use bevy::{math::Mat4, transform::components::GlobalTransform};
+
+    #[test]
+    fn out_of_bounds_indices_fail() {
+        let mut mesh = rectangle_mesh((2, 2), (Vec3::X, Vec3::Y), Vec3::Z);
+        mesh.set_indices(Some(Indices::U32(vec![0, 1, 4])));
+        assert!(matches!(
+            ClothRendering::init(&mesh, NormalComputing::None),
+            Err(Error::IndexOutOfBounds {
+                index: 4,
+                vertex_count: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn tearing_keeps_attribute_lengths() {
+        let mut mesh = rectangle_mesh((3, 3), (Vec3::X, Vec3::Y), Vec3::Z);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[1.0; 4]; 9]);
+        let mut rendering = ClothRendering::init(&mesh, NormalComputing::None).unwrap();
+        let mut cloth = Cloth::new(
+            &rendering.vertex_positions,
+            &rendering.indices,
+            HashMap::default(),
+            StickGeneration::Triangles,
+            StickLen::Auto,
+            StickMode::default(),
+            &Mat4::IDENTITY,
+        );
+        assert!(cloth.tear_stick([1, 4]));
+        for split in cloth.drain_tears().iter().flat_map(|t| &t.splits) {
+            rendering.split_vertex(split);
+        }
+        rendering.update_positions(cloth.compute_vertex_positions(&GlobalTransform::IDENTITY));
+        rendering.apply(&mut mesh);
+        rendering.mark_applied();
+        let vertex_count = mesh.count_vertices();
+        assert!(vertex_count > 9);
+        assert_eq!(mesh.attributes().count(), 4);
+        for (_, attribute) in mesh.attributes() {
+            assert_eq!(attribute.len(), vertex_count);
+        }
+        // The topology is only rewritten after a tear
+        mesh.set_indices(None);
+        rendering.apply(&mut mesh);
+        assert!(mesh.indices().is_none());
+    }
+
+    #[test]
+    fn custom_attributes_follow_flat_normals() {
+        let mut mesh = rectangle_mesh((2, 2), (Vec3::X, Vec3::Y), Vec3::Z);
+        let mut rendering = ClothRendering::init(&mesh, NormalComputing::FlatNormals).unwrap();
+        rendering.vertex_strains = Some(Vec::new());
+        let point_strains = [0.0, 1.0, 2.0, 3.0];
+        rendering.update_strains(&point_strains);
+        rendering.update_velocities([Vec3::X; 4].into_iter());
+        rendering.apply(&mut mesh);
+        let Some(VertexAttributeValues::Float32(strains)) =
+            mesh.attribute(ClothRendering::ATTRIBUTE_STRAIN)
+        else {
+            panic!("Missing strain attribute");
+        };
+        let expected: Vec<f32> = rendering
+            .indices
+            .iter()
+            .map(|i| point_strains[*i as usize])
+            .collect();
+        assert_eq!(strains, &expected);
+        assert!(mesh.attribute(ClothRendering::ATTRIBUTE_VELOCITY).is_none());
+    }
+}
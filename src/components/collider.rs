@@ -1,4 +1,4 @@
-use bevy::{ecs::component::Component, reflect::Reflect};
+use bevy::{ecs::component::Component, math::Vec3, reflect::Reflect, render::primitives::Aabb};
 
 /// Enables collisions on a cloth entity
 ///
@@ -16,6 +16,11 @@ pub struct ClothCollider {
     /// Defines the velocity reduction coefficient for dynamic rigibodies
     /// colliding with the cloth, improving the cloth effect.
     pub dampen_others: Option<f32>,
+    /// Enables continuous collision detection: the motion of every cloth
+    /// point during the last frame is swept against the colliders, preventing
+    /// fast points from passing through thin colliders. Increases the
+    /// collision cost
+    pub continuous: bool,
 }
 
 impl Default for ClothCollider {
@@ -24,6 +29,17 @@ impl Default for ClothCollider {
             offset: 0.25,
             velocity_coefficient: 1.0,
             dampen_others: None,
+            continuous: false,
         }
     }
 }
+
+impl ClothCollider {
+    /// Computes the half extents of the cuboid collider built around the
+    /// cloth `aabb`
+    #[inline]
+    #[must_use]
+    pub fn half_extents(&self, aabb: &Aabb) -> Vec3 {
+        Vec3::from(aabb.half_extents) + self.offset
+    }
+}
@@ -22,6 +22,90 @@ pub enum AccelerationSmoothing {
     FixedCoefficient(f32),
 }
 
+/// Defines how the cloth sticks constraints are solved.
+#[derive(Debug, Copy, Clone, Default, Reflect, PartialEq, Eq)]
+pub enum ClothSolver {
+    #[default]
+    /// Default solver, stick points are directly moved to match the stick
+    /// target length. The cloth stiffness depends on
+    /// [`ClothConfig::sticks_computation_depth`] and on the frame rate.
+    Projection,
+    /// Extended Position Based Dynamics solver. Every stick has a compliance
+    /// (See [`StickMode::Compliant`]) and a Lagrange multiplier, keeping the
+    /// cloth stiffness consistent whatever the computation depth and the
+    /// frame rate.
+    ///
+    /// [`StickMode::Compliant`]: crate::stick::StickMode::Compliant
+    Xpbd,
+}
+
+/// Defines in which order the cloth sticks corrections are applied.
+///
+/// Note: Only used by [`ClothSolver::Projection`]
+#[derive(Debug, Copy, Clone, Default, Reflect, PartialEq, Eq)]
+pub enum ClothIteration {
+    #[default]
+    /// Default iteration, every stick correction is directly applied and used
+    /// by the next sticks. Converges faster but depends on the sticks order.
+    GaussSeidel,
+    /// Every stick correction is computed from the same positions, then the
+    /// corrections are averaged per point and applied. Converges slower but
+    /// gives symmetric results, independent of the sticks order.
+    Jacobi,
+}
+
+/// Defines the time step used to simulate the cloth physics.
+#[derive(Debug, Copy, Clone, Default, Reflect)]
+pub enum ClothTimeStep {
+    #[default]
+    /// The cloth is simulated once per frame, using the elapsed time since
+    /// last frame (delta time)
+    Variable,
+    /// The cloth is simulated at a fixed `frequency`, independently of the
+    /// frame rate. Every step is divided in `substeps` and the rendered
+    /// positions are interpolated between the last two simulated states.
+    ///
+    /// Note: [`AccelerationSmoothing::FixedCoefficient`] is applied on every
+    /// substep
+    Fixed {
+        /// Simulation frequency in Hz (steps per second)
+        frequency: f32,
+        /// Number of substeps per simulation step
+        substeps: u8,
+    },
+}
+
+impl ClothTimeStep {
+    /// Maximum amount of fixed steps simulated in a single frame. Any
+    /// remaining time is dropped to avoid a spiral of death on slow frames.
+    pub const MAX_STEPS_PER_FRAME: u8 = 8;
+
+    /// Fixed step frequency in Hz used in deterministic mode when the time
+    /// step is [`Self::Variable`] (See [`ClothConfig::deterministic`])
+    pub const DETERMINISTIC_FREQUENCY: f32 = 60.0;
+
+    /// Retrieves the duration in seconds of a fixed step, `None` for
+    /// [`Self::Variable`]
+    #[inline]
+    #[must_use]
+    pub fn step_duration(&self) -> Option<f32> {
+        match self {
+            Self::Variable => None,
+            Self::Fixed { frequency, .. } => Some(1.0 / frequency.max(f32::EPSILON)),
+        }
+    }
+
+    /// Retrieves the amount of substeps per step, `1` for [`Self::Variable`]
+    #[inline]
+    #[must_use]
+    pub fn substeps(&self) -> u8 {
+        match self {
+            Self::Variable => 1,
+            Self::Fixed { substeps, .. } => (*substeps).max(1),
+        }
+    }
+}
+
 /// Cloth physics configuration.
 ///
 /// Used as a resource, it is used as a global configuration for every cloth
@@ -44,6 +128,38 @@ pub struct ClothConfig {
     pub sticks_computation_depth: u8,
     /// Smoothing behaviour for gravity and winds
     pub acceleration_smoothing: AccelerationSmoothing,
+    /// Sticks constraints solver
+    pub solver: ClothSolver,
+    /// Sticks constraints iteration order
+    pub iteration: ClothIteration,
+    /// Simulation time step
+    pub time_step: ClothTimeStep,
+    /// Aerodynamic drag coefficient, pushing the cloth triangles along the
+    /// wind. If both the drag and lift coefficients are `0.0` the wind is
+    /// applied as a uniform acceleration
+    pub drag_coefficient: f32,
+    /// Aerodynamic lift coefficient, pushing the cloth triangles
+    /// perpendicularly to the wind. If both the drag and lift coefficients
+    /// are `0.0` the wind is applied as a uniform acceleration
+    pub lift_coefficient: f32,
+    /// Enables the deterministic mode, giving bit-identical simulations
+    /// across runs on the same platform:
+    /// - A [`ClothTimeStep::Variable`] time step is replaced by a fixed step
+    ///   at [`ClothTimeStep::DETERMINISTIC_FREQUENCY`]
+    /// - Winds are evaluated at the simulated time instead of the app time
+    /// - Steps above [`ClothTimeStep::MAX_STEPS_PER_FRAME`] are delayed to the
+    ///   next frames instead of being dropped
+    pub deterministic: bool,
+    /// Maximum point displacement per step under which a cloth is considered
+    /// at rest. A cloth at rest for [`Self::sleep_steps`] consecutive steps
+    /// falls asleep and is no longer simulated until it wakes up.
+    /// Steps are frames, or fixed steps regardless of their substeps (See
+    /// [`Self::time_step`]).
+    /// If set to `0.0` the cloths never sleep
+    pub sleep_threshold: f32,
+    /// Amount of consecutive steps at rest before a cloth falls asleep, see
+    /// [`Self::sleep_threshold`]. Substeps are not counted
+    pub sleep_steps: u16,
 }
 
 impl ClothConfig {
@@ -82,6 +198,35 @@ impl ClothConfig {
         }
     }
 
+    /// Returns `true` if the wind is applied through the aerodynamic model,
+    /// see [`Self::drag_coefficient`] and [`Self::lift_coefficient`]
+    #[inline]
+    #[must_use]
+    pub fn aerodynamics_enabled(&self) -> bool {
+        self.drag_coefficient != 0.0 || self.lift_coefficient != 0.0
+    }
+
+    /// Returns `true` if the cloths are allowed to sleep, see
+    /// [`Self::sleep_threshold`]
+    #[inline]
+    #[must_use]
+    pub fn sleeping_enabled(&self) -> bool {
+        self.sleep_threshold > 0.0
+    }
+
+    /// Retrieves the time step actually used by the simulation, see
+    /// [`Self::deterministic`]
+    #[must_use]
+    pub const fn effective_time_step(&self) -> ClothTimeStep {
+        match self.time_step {
+            ClothTimeStep::Variable if self.deterministic => ClothTimeStep::Fixed {
+                frequency: ClothTimeStep::DETERMINISTIC_FREQUENCY,
+                substeps: 1,
+            },
+            time_step => time_step,
+        }
+    }
+
     /// Initializes a cloth config with no gravity force
     #[must_use]
     #[inline]
@@ -100,6 +245,14 @@ impl Default for ClothConfig {
             friction: 0.02,
             sticks_computation_depth: 5,
             acceleration_smoothing: Default::default(),
+            solver: Default::default(),
+            iteration: Default::default(),
+            time_step: Default::default(),
+            drag_coefficient: 0.0,
+            lift_coefficient: 0.0,
+            deterministic: false,
+            sleep_threshold: 0.0,
+            sleep_steps: 60,
         }
     }
 }
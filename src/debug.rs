@@ -0,0 +1,125 @@
+use crate::stick::StickMode;
+use bevy::{
+    ecs::prelude::Resource, prelude::ReflectResource, reflect::Reflect, render::color::Color,
+};
+
+/// Defines how the debug sticks are colored
+#[derive(Debug, Copy, Clone, Default, Reflect)]
+pub enum StickColoring {
+    /// The sticks are colored according to their [`StickMode`] (See
+    /// [`ClothDebugConfig::stick_mode_color`])
+    #[default]
+    Mode,
+    /// The sticks are colored according to their current strain: green at
+    /// rest, red when stretched and blue when compressed
+    Strain {
+        /// Strain ratio giving the full stretched or compressed color
+        max_strain: f32,
+    },
+}
+
+/// Cloth debug rendering configuration resource, used by the
+/// [`ClothDebugPlugin`]
+///
+/// [`ClothDebugPlugin`]: crate::ClothDebugPlugin
+#[derive(Debug, Clone, Resource, Reflect)]
+#[reflect(Resource)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ClothDebugConfig {
+    /// Draws the cloth sticks
+    pub sticks: bool,
+    /// Sticks coloring
+    pub stick_coloring: StickColoring,
+    /// Draws the anchored points, with lines to their custom target if any
+    pub anchors: bool,
+    /// Draws the points velocities
+    pub velocities: bool,
+    /// Scale applied to the drawn velocities, expressed as displacements per
+    /// step
+    pub velocity_scale: f32,
+    /// Draws the cloth collider bounds
+    #[cfg(feature = "rapier_collisions")]
+    pub collider: bool,
+}
+
+impl Default for ClothDebugConfig {
+    fn default() -> Self {
+        Self {
+            sticks: true,
+            stick_coloring: StickColoring::default(),
+            anchors: true,
+            velocities: false,
+            velocity_scale: 10.0,
+            #[cfg(feature = "rapier_collisions")]
+            collider: true,
+        }
+    }
+}
+
+impl ClothDebugConfig {
+    /// Color of the anchored points
+    pub const ANCHOR_COLOR: Color = Color::ORANGE_RED;
+    /// Color of the points velocities
+    pub const VELOCITY_COLOR: Color = Color::PURPLE;
+    /// Color of the cloth collider bounds
+    pub const COLLIDER_COLOR: Color = Color::GREEN;
+
+    /// Retrieves the debug color of a stick with the given `mode`
+    #[must_use]
+    pub const fn stick_mode_color(mode: StickMode) -> Color {
+        match mode {
+            StickMode::Fixed { .. } => Color::WHITE,
+            StickMode::Spring { .. } => Color::YELLOW,
+            StickMode::Compliant { .. } => Color::CYAN,
+        }
+    }
+
+    /// Retrieves the debug color of a stick according to the stick coloring
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The stick mode
+    /// * `length` - The current stick length
+    /// * `rest_length` - The stick rest length
+    #[must_use]
+    pub fn stick_color(&self, mode: StickMode, length: f32, rest_length: f32) -> Color {
+        match self.stick_coloring {
+            StickColoring::Mode => Self::stick_mode_color(mode),
+            StickColoring::Strain { max_strain } => {
+                let strain = (length - rest_length) / rest_length.max(f32::EPSILON);
+                let ratio = (strain / max_strain.max(f32::EPSILON)).clamp(-1.0, 1.0);
+                if ratio >= 0.0 {
+                    Color::rgb(ratio, 1.0 - ratio, 0.0)
+                } else {
+                    Color::rgb(0.0, 1.0 + ratio, -ratio)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strain_stick_colors() {
+        let config = ClothDebugConfig {
+            stick_coloring: StickColoring::Strain { max_strain: 0.5 },
+            ..Default::default()
+        };
+        let mode = StickMode::default();
+        assert_eq!(
+            config.stick_color(mode, 1.0, 1.0),
+            Color::rgb(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            config.stick_color(mode, 2.0, 1.0),
+            Color::rgb(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            config.stick_color(mode, 0.75, 1.0),
+            Color::rgb(0.0, 0.5, 0.5)
+        );
+    }
+}
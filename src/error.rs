@@ -22,4 +22,17 @@ pub enum Error {
     /// The mesh associated to a cloth has no indices
     #[error("Cloth requires meshes with indexed geometry")]
     MissingIndices,
+    /// The mesh associated to a cloth has an index out of the vertex bounds
+    #[error(
+        "Mesh associated to cloth has an out of bounds index {index} for {vertex_count} vertices"
+    )]
+    IndexOutOfBounds {
+        /// The invalid index
+        index: u32,
+        /// The mesh vertex count
+        vertex_count: usize,
+    },
+    /// The cloth builder anchors a vertex out of the mesh vertex bounds
+    #[error("Anchored vertex id {0} is out of the mesh vertex bounds")]
+    AnchoredVertexOutOfBounds(usize),
 }
@@ -0,0 +1,82 @@
+use crate::{components::cloth::StickId, error::Error, vertex_anchor::VertexAnchor};
+use bevy::ecs::{entity::Entity, event::Event};
+
+/// Event sent when a cloth entity is initialized from its [`ClothBuilder`]
+/// and starts being simulated
+///
+/// [`ClothBuilder`]: crate::prelude::ClothBuilder
+#[derive(Debug, Clone, Event)]
+pub struct ClothReady {
+    /// The cloth entity
+    pub entity: Entity,
+}
+
+/// Event sent when a cloth entity fails to initialize from its
+/// [`ClothBuilder`], the entity is then not simulated
+///
+/// [`ClothBuilder`]: crate::prelude::ClothBuilder
+#[derive(Debug, Clone, Event)]
+pub struct ClothInitFailed {
+    /// The cloth entity
+    pub entity: Entity,
+    /// The initialization error
+    pub error: Error,
+}
+
+/// Event sent when an already simulated cloth entity is initialized again,
+/// after its [`ClothBuilder`] or its mesh changed. The simulated point
+/// positions are kept if the mesh topology didn't change
+///
+/// [`ClothBuilder`]: crate::prelude::ClothBuilder
+#[derive(Debug, Clone, Event)]
+pub struct ClothReset {
+    /// The cloth entity
+    pub entity: Entity,
+}
+
+/// Event sent when a cloth comes to rest and falls asleep, see
+/// [`ClothConfig::sleep_threshold`]
+///
+/// [`ClothConfig::sleep_threshold`]: crate::config::ClothConfig::sleep_threshold
+#[derive(Debug, Clone, Event)]
+pub struct ClothSettled {
+    /// The cloth entity
+    pub entity: Entity,
+}
+
+/// Event sent when a cloth tears, after its rendering was split
+#[derive(Debug, Clone, Event)]
+pub struct ClothTornEvent {
+    /// The cloth entity
+    pub entity: Entity,
+    /// The torn sticks
+    pub sticks: Vec<StickId>,
+    /// The ids of the triangles sharing a torn stick edge
+    pub triangles: Vec<usize>,
+}
+
+/// Event sent when a cloth point is pinned, unpinned or anchored to a new
+/// anchor
+#[derive(Debug, Clone, Event)]
+pub struct ClothAnchorChanged {
+    /// The cloth entity
+    pub entity: Entity,
+    /// The cloth point id
+    pub point: usize,
+    /// The previous point anchor, `None` if the point was free
+    pub previous: Option<VertexAnchor>,
+    /// The new point anchor, `None` if the point was unpinned
+    pub anchor: Option<VertexAnchor>,
+}
+
+/// Event sent when a cloth anchor breaks, see
+/// [`VertexAnchor::break_threshold`]
+#[derive(Debug, Clone, Event)]
+pub struct ClothAnchorBroken {
+    /// The cloth entity
+    pub entity: Entity,
+    /// The cloth point id
+    pub point: usize,
+    /// The broken anchor
+    pub anchor: VertexAnchor,
+}
@@ -0,0 +1,160 @@
+use crate::{components::cloth::Cloth, vertex_anchor::VertexAnchor};
+use bevy::{
+    ecs::prelude::{Component, Entity, Resource},
+    input::mouse::MouseButton,
+    math::{Ray, Vec3, Vec3A},
+    prelude::ReflectResource,
+    reflect::Reflect,
+};
+
+/// Cloth grabbing configuration resource, used by the [`ClothGrabPlugin`]
+///
+/// [`ClothGrabPlugin`]: crate::ClothGrabPlugin
+#[derive(Debug, Clone, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ClothGrabConfig {
+    /// The mouse button grabbing the cloth points
+    pub button: MouseButton,
+    /// Optional compliance of the grab anchor (See
+    /// [`VertexAnchor::compliance`]). If not set, the grabbed point rigidly
+    /// follows the cursor
+    ///
+    /// [`VertexAnchor::compliance`]: crate::vertex_anchor::VertexAnchor::compliance
+    pub compliance: Option<f32>,
+}
+
+impl Default for ClothGrabConfig {
+    fn default() -> Self {
+        Self {
+            button: MouseButton::Left,
+            compliance: None,
+        }
+    }
+}
+
+/// A cloth point grabbed by the cursor
+#[derive(Debug, Copy, Clone, Reflect)]
+pub struct GrabbedPoint {
+    /// The grabbed cloth entity
+    pub cloth: Entity,
+    /// The grabbed point id
+    pub point: usize,
+    /// The drag target entity, following the cursor, the point is anchored to
+    pub target: Entity,
+    /// Distance of the drag target along the cursor ray
+    pub distance: f32,
+    /// The soft anchor of the point before it was grabbed and its local
+    /// position, restored on release
+    pub previous_anchor: Option<(VertexAnchor, Vec3)>,
+}
+
+/// Cloth grabbing state resource, used by the [`ClothGrabPlugin`]
+///
+/// [`ClothGrabPlugin`]: crate::ClothGrabPlugin
+#[derive(Debug, Clone, Default, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ClothGrab {
+    /// The currently grabbed cloth point, if any
+    pub grabbed: Option<GrabbedPoint>,
+}
+
+/// Marker component for the drag target entity of a grabbed cloth point
+#[derive(Debug, Copy, Clone, Component, Reflect)]
+pub struct ClothGrabTarget;
+
+/// Picks the cloth point grabbed by `ray`: the closest point of the nearest
+/// cloth triangle hit by the ray (See [`Cloth::cast_ray`]) which is not
+/// rigidly anchored.
+///
+/// If every point of the hit triangle is rigidly anchored, the free cloth
+/// point closest to the hit point is picked instead.
+///
+/// Returns the point id and its distance along the ray
+#[must_use]
+pub fn pick_point(cloth: &Cloth, ray: Ray) -> Option<(usize, f32)> {
+    let hit = cloth.cast_ray(ray)?;
+    // The closest triangle point has the highest barycentric coordinate
+    let point = cloth.triangles[hit.triangle]
+        .into_iter()
+        .zip(hit.barycentric.to_array())
+        .filter(|(i, _)| !cloth.is_pinned(*i))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+        .or_else(|| {
+            let hit_point = Vec3A::from(hit.point);
+            cloth
+                .current_point_positions
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !cloth.is_pinned(*i))
+                .min_by(|(_, a), (_, b)| {
+                    a.distance_squared(hit_point)
+                        .total_cmp(&b.distance_squared(hit_point))
+                })
+                .map(|(i, _)| i)
+        })?;
+    let position = Vec3::from(cloth.current_point_positions[point]);
+    Some((point, (position - ray.origin).dot(ray.direction)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stick::{StickGeneration, StickLen, StickMode};
+    use bevy::{math::Mat4, utils::HashMap};
+
+    #[test]
+    fn picks_soft_anchored_points() {
+        let pick = |anchor: VertexAnchor| {
+            let cloth = Cloth::new(
+                &[Vec3::ZERO, Vec3::X, Vec3::Y],
+                &[0, 1, 2],
+                HashMap::from([(0, anchor)]),
+                StickGeneration::Triangles,
+                StickLen::Auto,
+                StickMode::default(),
+                &Mat4::IDENTITY,
+            );
+            pick_point(
+                &cloth,
+                Ray {
+                    origin: Vec3::new(0.1, 0.1, 1.0),
+                    direction: Vec3::NEG_Z,
+                },
+            )
+            .map(|(point, _)| point)
+        };
+        let soft = VertexAnchor {
+            compliance: Some(0.1),
+            ..Default::default()
+        };
+        assert_eq!(pick(soft), Some(0));
+        assert_ne!(pick(VertexAnchor::default()), Some(0));
+    }
+
+    #[test]
+    fn picks_nearest_free_point_of_pinned_triangles() {
+        let mut cloth = Cloth::new(
+            &[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::X * 2.0, Vec3::X * 3.0],
+            &[0, 1, 2, 1, 3, 4],
+            HashMap::from([0, 1, 2].map(|i| (i, VertexAnchor::default()))),
+            StickGeneration::Triangles,
+            StickLen::Auto,
+            StickMode::default(),
+            &Mat4::IDENTITY,
+        );
+        let ray = Ray {
+            origin: Vec3::new(0.1, 0.1, 1.0),
+            direction: Vec3::NEG_Z,
+        };
+        assert_eq!(pick_point(&cloth, ray).map(|(point, _)| point), Some(3));
+        // Fully pinned cloths can't be grabbed
+        cloth
+            .anchored_points
+            .insert(3, (VertexAnchor::default(), Vec3::ZERO));
+        cloth
+            .anchored_points
+            .insert(4, (VertexAnchor::default(), Vec3::ZERO));
+        assert!(pick_point(&cloth, ray).is_none());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::components::cloth_rendering::ClothRendering;
+use bevy::{
+    asset::HandleUntyped,
+    pbr::{Material, MaterialPipeline, MaterialPipelineKey},
+    reflect::{TypePath, TypeUuid},
+    render::{
+        color::Color,
+        mesh::{Mesh, MeshVertexBufferLayout},
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, Shader, ShaderRef, SpecializedMeshPipelineError,
+        },
+    },
+};
+
+/// Handle of the [`ClothHeatmapMaterial`] shader
+pub const CLOTH_HEATMAP_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7_412_385_610_294_817_203);
+
+/// Debug material displaying the cloth strain as a heatmap.
+///
+/// The rest color blends to the stretched color as the strain reaches
+/// [`Self::max_strain`], and to the compressed color as it reaches
+/// `-max_strain`.
+///
+/// Note: requires the cloth mesh to have the
+/// [`ClothRendering::ATTRIBUTE_STRAIN`] attribute (See
+/// `ClothBuilder::with_strain_attribute`) and the [`ClothHeatmapPlugin`]
+///
+/// [`ClothHeatmapPlugin`]: crate::ClothHeatmapPlugin
+#[derive(Debug, Clone, AsBindGroup, TypeUuid, TypePath)]
+#[uuid = "2f5b6c8e-3d1a-4b7e-9c0f-6a2e8d4b1f37"]
+pub struct ClothHeatmapMaterial {
+    /// Color of the points at rest
+    #[uniform(0)]
+    pub rest_color: Color,
+    /// Color of the fully stretched points
+    #[uniform(0)]
+    pub stretched_color: Color,
+    /// Color of the fully compressed points
+    #[uniform(0)]
+    pub compressed_color: Color,
+    /// Strain giving the full stretched or compressed color
+    #[uniform(0)]
+    pub max_strain: f32,
+}
+
+impl Default for ClothHeatmapMaterial {
+    fn default() -> Self {
+        Self {
+            rest_color: Color::GREEN,
+            stretched_color: Color::RED,
+            compressed_color: Color::BLUE,
+            max_strain: 0.2,
+        }
+    }
+}
+
+impl Material for ClothHeatmapMaterial {
+    fn vertex_shader() -> ShaderRef {
+        CLOTH_HEATMAP_SHADER_HANDLE.typed().into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        CLOTH_HEATMAP_SHADER_HANDLE.typed().into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ClothRendering::ATTRIBUTE_STRAIN.at_shader_location(1),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+#import bevy_pbr::mesh_bindings   mesh
+#import bevy_pbr::mesh_functions  mesh_position_local_to_clip
+
+struct ClothHeatmapMaterial {
+    rest_color: vec4<f32>,
+    stretched_color: vec4<f32>,
+    compressed_color: vec4<f32>,
+    max_strain: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: ClothHeatmapMaterial;
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) strain: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) strain: f32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(
+        mesh.model,
+        vec4<f32>(vertex.position, 1.0),
+    );
+    out.strain = vertex.strain;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let ratio = clamp(in.strain / max(material.max_strain, 0.0001), -1.0, 1.0);
+    if ratio >= 0.0 {
+        return mix(material.rest_color, material.stretched_color, ratio);
+    }
+    return mix(material.rest_color, material.compressed_color, -ratio);
+}
@@ -77,6 +77,99 @@
 //! }
 //! ```
 //!
+//! #### Mass-spring sticks
+//!
+//! `StickGeneration::MassSpring` generates the classic structural, shear and
+//! bend springs from the mesh topology. Each `StickCategory` can use its own
+//! stick mode and length:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     .with_stick_generation(StickGeneration::MassSpring)
+//!     // Soft shear sticks
+//!     .with_category_stick_mode(
+//!         StickCategory::Shear,
+//!         StickMode::Spring {
+//!             min_percent: 0.8,
+//!             max_percent: 1.2,
+//!             tear_threshold: None,
+//!         },
+//!     )
+//!     // Slightly shorter bend sticks
+//!     .with_category_stick_length(StickCategory::Bend, StickLen::Coefficient(0.98));
+//! ```
+//!
+//! #### Tearing
+//!
+//! Cloth sticks can tear when stretched above the tearing threshold of their
+//! `StickMode`, the cloth mesh is then split by duplicating its vertices along the
+//! tear and a `ClothTornEvent` is sent:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     // Sticks tear when stretched above 150% of their length
+//!     .with_stick_mode(StickMode::Fixed {
+//!         tear_threshold: Some(1.5),
+//!     })
+//!     // Shear sticks never tear
+//!     .with_category_stick_mode(
+//!         StickCategory::Shear,
+//!         StickMode::Fixed {
+//!             tear_threshold: None,
+//!         },
+//!     );
+//! ```
+//!
+//! #### Bending
+//!
+//! By default the cloth has no bending resistance. Setting a bending stiffness
+//! generates dihedral bending constraints between every pair of adjacent
+//! triangles, allowing stiff materials like leather or canvas to keep their
+//! shape:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     // Bending stiffness between 0.0 and 1.0
+//!     .with_bending_stiffness(0.5)
+//!     // The rest angle will be the initial angle between the triangles
+//!     .with_bending_angle(BendingAngle::Auto);
+//! ```
+//!
+//! #### Volume preservation
+//!
+//! Closed meshes, like spheres, can keep their enclosed volume through an
+//! internal pressure. The target volume ratio can be changed at runtime with
+//! `Cloth::set_volume_ratio` to inflate or deflate the cloth:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     // Inflates the cloth to 120% of its initial volume, with full pressure
+//!     .with_volume_preservation(1.2, 1.0);
+//! ```
+//!
+//! #### Vertex masses
+//!
+//! Every cloth vertex has a mass of `1.0` by default, heavier vertices are less
+//! moved by the cloth constraints. Custom masses can be set per vertex id:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     // A heavy bottom bar on vertices 90 to 99
+//!     .with_vertex_masses((90..100).map(|i| (i, 10.0)));
+//! ```
+//!
+//! Weights can also be added at runtime through `Cloth::add_weight`.
+//!
 //! #### Vertex anchoring
 //!
 //! Specifying vertex anchors allows to pin some cloth vertices to various
@@ -149,6 +242,49 @@
 //!   vertices an the target
 //! - use world space pinning and ignore the target's rotation for example
 //! - override the vertex positions, using only the offset
+//! - define soft anchors through a `compliance`, pulling the vertices toward the
+//!   target instead of pinning them
+//! - define breakable anchors through a `break_threshold`, sending a
+//!   `ClothAnchorBroken` event when they break
+//!
+//! Anchors can also be edited at runtime through `Cloth::pin_point` and
+//! `Cloth::unpin_point`, an unpinned point keeping its velocity. Every change
+//! sends a `ClothAnchorChanged` event:
+//!
+//! ```rust
+//! use bevy::prelude::*;
+//! use bevy_silk::{components::cloth::Cloth, prelude::*};
+//!
+//! fn cut_loose(mut cloths: Query<&mut Cloth>) {
+//!     for mut cloth in &mut cloths {
+//!         let pinned: Vec<usize> = cloth.anchored_points.keys().copied().collect();
+//!         for point in pinned {
+//!             cloth.unpin_point(point);
+//!         }
+//!     }
+//! }
+//!
+//! fn nail(mut cloths: Query<(&mut Cloth, &GlobalTransform)>) {
+//!     for (mut cloth, transform) in &mut cloths {
+//!         cloth.pin_point(0, VertexAnchor::default(), transform);
+//!     }
+//! }
+//! ```
+//!
+//! #### Long range attachments
+//!
+//! Tall cloths anchored only on one side, like capes, tend to overstretch.
+//! Long range attachments (tethers) prevent every free vertex from going
+//! further from its nearest anchored vertex than its rest distance:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     .with_pinned_vertex_ids(0..9)
+//!     // Vertices can stretch up to 10% away from their nearest anchor
+//!     .with_tethers(1.1);
+//! ```
 //!
 //! ### Configuration
 //!
@@ -166,7 +302,15 @@
 //!         gravity: Vec3::new(0.0, -9.81, 0.0),
 //!         friction: 0.02,
 //!         sticks_computation_depth: 5,
-//!         acceleration_smoothing: AccelerationSmoothing::default()
+//!         acceleration_smoothing: AccelerationSmoothing::default(),
+//!         solver: ClothSolver::Projection,
+//!         iteration: ClothIteration::GaussSeidel,
+//!         time_step: ClothTimeStep::Variable,
+//!         drag_coefficient: 0.0,
+//!         lift_coefficient: 0.0,
+//!         deterministic: false,
+//!         sleep_threshold: 0.0,
+//!         sleep_steps: 60,
 //!     })
 //!     .add_plugin(ClothPlugin)
 //!     // ... Add your resources and systems
@@ -177,6 +321,105 @@
 //! `ClothConfig` can also be used as a *component* to override the global
 //! configuration.
 //!
+//! #### XPBD solver
+//!
+//! By default sticks are solved through direct position projection, making
+//! the cloth stiffness depend on `sticks_computation_depth` and on the frame
+//! rate. Setting `ClothConfig::solver` to `ClothSolver::Xpbd` enables a
+//! compliance based solver, keeping a consistent stiffness. Use
+//! `StickMode::Compliant` to define soft sticks:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new()
+//!     .with_stick_mode(StickMode::Compliant {
+//!         compliance: 1e-4,
+//!         tear_threshold: None,
+//!     });
+//! ```
+//!
+//! #### Jacobi iteration
+//!
+//! By default stick corrections are applied one after the other (Gauss-Seidel
+//! iteration), which converges fast but depends on the sticks order. Setting
+//! `ClothConfig::iteration` to `ClothIteration::Jacobi` averages the corrections
+//! of every point instead, giving symmetric and order independent results.
+//!
+//! #### Fixed time step
+//!
+//! By default the cloth is simulated once per frame, making the simulation
+//! depend on the frame rate. Setting `ClothConfig::time_step` to
+//! `ClothTimeStep::Fixed` simulates the cloth at a fixed frequency, divided in
+//! substeps. The rendered positions are then interpolated between the last two
+//! simulated states:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let config = ClothConfig {
+//!     time_step: ClothTimeStep::Fixed {
+//!         frequency: 120.0,
+//!         substeps: 2,
+//!     },
+//!     ..Default::default()
+//! };
+//! ```
+//!
+//! #### Deterministic simulation
+//!
+//! Setting `ClothConfig::deterministic` to `true` gives bit-identical
+//! simulations across runs on the same platform, useful for lockstep multiplayer
+//! or replays. The cloth is simulated with a fixed time step, winds are
+//! evaluated at the simulated time and `Cloth::state_hash` allows to compare
+//! cloth states:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let config = ClothConfig {
+//!     deterministic: true,
+//!     ..Default::default()
+//! };
+//! ```
+//!
+//! #### Sleeping
+//!
+//! Setting `ClothConfig::sleep_threshold` lets idle cloths fall asleep: once no
+//! point moved further than the threshold for `ClothConfig::sleep_steps`
+//! consecutive steps (frames, or fixed steps regardless of their substeps), the
+//! cloth is no longer simulated and its mesh no longer updated. A sleeping cloth
+//! wakes up when an anchor moves, the wind changes, a collision occurs or
+//! `Cloth::apply_impulse` is called:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let config = ClothConfig {
+//!     sleep_threshold: 0.001,
+//!     sleep_steps: 60,
+//!     ..Default::default()
+//! };
+//! ```
+//!
+//! ### Lifecycle events
+//!
+//! The cloth plugin sends events allowing to react to the cloths lifecycle:
+//! - `ClothReady` when a cloth is initialized from its `ClothBuilder`
+//! - `ClothInitFailed` when a cloth fails to initialize, for example with an
+//!   invalid mesh, carrying the `Error`
+//! - `ClothReset` when a simulated cloth is initialized again
+//! - `ClothSettled` when a cloth comes to rest and falls asleep
+//!
+//! A cloth is rebuilt when its `ClothBuilder` changes, when its `Handle<Mesh>` is
+//! replaced or when its mesh asset is modified (e.g. hot reloaded), allowing live
+//! tweaking. If the mesh topology is unchanged, the simulated point positions and
+//! the anchors and masses edited at runtime (`Cloth::pin_point`,
+//! `Cloth::unpin_point` and `Cloth::set_point_mass`) are kept.
+//!
+//! > Note: Each cloth should use its own mesh asset, as the mesh is updated by
+//! > the simulation
+//!
 //! ## Wind
 //!
 //! You may add wind forces to the simulation for a more dynamic clothing
@@ -209,6 +452,22 @@
 //! }
 //! ```
 //!
+//! By default winds are applied as a uniform acceleration on every cloth vertex.
+//! Setting `ClothConfig::drag_coefficient` and `ClothConfig::lift_coefficient`
+//! enables an aerodynamic model, computing drag and lift forces on every cloth
+//! triangle from its normal, its area and its velocity relative to the wind.
+//! A cloth edge-on to the wind is then barely pushed, and flags or sails ripple:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let config = ClothConfig {
+//!     drag_coefficient: 0.5,
+//!     lift_coefficient: 0.3,
+//!     ..Default::default()
+//! };
+//! ```
+//!
 //! > Check the flag example for simple wind effect.
 //!
 //! ## Collisions
@@ -242,9 +501,136 @@
 //!
 //! You can customize what collisions will be checked through a `CollisionGroups` (See the [rapier docs](https://rapier.rs/docs/user_guides/bevy_plugin/colliders#collision-groups-and-solver-groups)).
 //!
+//! Fast moving cloth points may pass through thin colliders in a single frame.
+//! Setting `ClothCollider::continuous` to `true` sweeps the motion of every point
+//! since the beginning of the frame, including all its steps and substeps, against
+//! the colliders to prevent it, at an extra cost.
+//!
 //! > Note: Collision support is still experimental for now and is not suited
 //! > for production use. Feedback is welcome !
 //!
+//! ## Self collision
+//!
+//! Cloths can collide with themselves, preventing folded or draped cloths from
+//! passing through themselves. Self collision is enabled with a thickness, the
+//! minimum distance between two cloth points not connected by a stick:
+//!
+//! ```rust
+//! use bevy_silk::prelude::*;
+//!
+//! let cloth = ClothBuilder::new().with_self_collision(0.05);
+//! ```
+//!
+//! The close points are found through a spatial hash rebuilt every step, keep
+//! the thickness close to the mesh vertex spacing for good performance.
+//!
+//! ## Queries
+//!
+//! The simulated cloth surfaces can be queried through the `ClothQuery` system
+//! parameter, or directly on a `Cloth` component, allowing to ray-cast the
+//! deformed cloth triangles or to retrieve the closest point of a cloth:
+//!
+//! ```rust
+//! use bevy::prelude::*;
+//! use bevy_silk::prelude::*;
+//!
+//! fn shoot(cloths: ClothQuery) {
+//!     let ray = Ray {
+//!         origin: Vec3::new(0.0, 1.0, 10.0),
+//!         direction: Vec3::NEG_Z,
+//!     };
+//!     if let Some((entity, hit)) = cloths.cast_ray(ray) {
+//!         println!("Hit triangle {} of {entity:?} at {}", hit.triangle, hit.point);
+//!     }
+//!     if let Some((entity, projection)) = cloths.closest_point(Vec3::ZERO) {
+//!         println!("Closest point of {entity:?}: {}", projection.point);
+//!     }
+//! }
+//! ```
+//!
+//! ## Grabbing
+//!
+//! Adding the `ClothGrabPlugin` allows to grab cloth points with the mouse: the
+//! cloth point under the cursor is anchored to a drag target following the cursor
+//! until the button is released. Rigidly anchored points can't be grabbed, soft
+//! anchored points return to their anchor once released. The `ClothGrabConfig`
+//! resource defines the mouse button and the anchor compliance:
+//!
+//! ```rust no_run
+//! use bevy::prelude::*;
+//! use bevy_silk::prelude::*;
+//!
+//! fn main() {
+//!   App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins((ClothPlugin, ClothGrabPlugin))
+//!     .insert_resource(ClothGrabConfig {
+//!         button: MouseButton::Right,
+//!         compliance: Some(1e-4),
+//!     })
+//!     .run();
+//! }
+//! ```
+//!
+//! ## Custom vertex attributes
+//!
+//! The cloth can export per-vertex data to the mesh as custom vertex attributes,
+//! allowing custom shaders to display stretch whitening, motion based wetness or
+//! wrinkles:
+//! - `ClothBuilder::with_strain_attribute` exports the strain of every vertex,
+//!   the average stretch ratio of its adjacent sticks, as
+//!   `ClothRendering::ATTRIBUTE_STRAIN` (`f32`)
+//! - `ClothBuilder::with_velocity_attribute` exports the local space velocity of
+//!   every vertex as `ClothRendering::ATTRIBUTE_VELOCITY` (`Vec3`)
+//!
+//! Enabling the `heatmap` feature provides the `ClothHeatmapPlugin` and the
+//! `ClothHeatmapMaterial`, a ready-made debug material displaying the cloth strain
+//! of meshes with the strain attribute:
+//!
+//! ```rust
+//! use bevy::prelude::*;
+//! use bevy_silk::prelude::*;
+//!
+//! fn spawn(mut commands: Commands, mut materials: ResMut<Assets<ClothHeatmapMaterial>>) {
+//!     commands.spawn((
+//!         MaterialMeshBundle {
+//!             // Add your mesh and custom data
+//!             material: materials.add(ClothHeatmapMaterial {
+//!                 max_strain: 0.1,
+//!                 ..Default::default()
+//!             }),
+//!             ..Default::default()
+//!         },
+//!         ClothBuilder::new().with_strain_attribute(),
+//!     ));
+//! }
+//! ```
+//!
+//! ## Debug rendering
+//!
+//! Enabling the `gizmos` feature provides the `ClothDebugPlugin`, drawing the cloth
+//! sticks, the anchored points with lines to their custom targets, the point
+//! velocities and, with `rapier_collisions`, the collider bounds. Sticks are colored
+//! either by `StickMode` or by their current strain, as defined by the
+//! `ClothDebugConfig` resource:
+//!
+//! ```rust no_run
+//! use bevy::prelude::*;
+//! use bevy_silk::prelude::*;
+//!
+//! fn main() {
+//!   App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins((ClothPlugin, ClothDebugPlugin))
+//!     .insert_resource(ClothDebugConfig {
+//!         stick_coloring: StickColoring::Strain { max_strain: 0.2 },
+//!         velocities: true,
+//!         ..Default::default()
+//!     })
+//!     .run();
+//! }
+//! ```
+//!
 //! ## Mesh utils
 //!
 //! `bevy_silk` provides a plane mesh generation function `rectangle_mesh`
@@ -279,20 +665,44 @@
     clippy::module_name_repetitions,
     clippy::redundant_pub_crate
 )]
+/// aerodynamics module
+pub mod aerodynamics;
+/// bending module
+pub mod bending;
 /// components module
 pub mod components;
 /// config module
 pub mod config;
+/// debug module
+#[cfg(feature = "gizmos")]
+pub mod debug;
 /// error module
 pub mod error;
+/// events module
+pub mod events;
+/// grab module
+pub mod grab;
+/// heatmap module
+#[cfg(feature = "heatmap")]
+pub mod heatmap;
 /// mesh module
 pub mod mesh;
+/// query module
+pub mod query;
+/// self collision module
+pub mod self_collision;
 /// stick module
 pub mod stick;
 /// systems module
 mod systems;
+/// tear module
+pub mod tear;
+/// tether module
+pub mod tether;
 /// vertex anchor module
 pub mod vertex_anchor;
+/// volume module
+pub mod volume;
 /// wind module
 pub mod wind;
 
@@ -304,15 +714,29 @@ pub mod prelude {
     #[cfg(feature = "rapier_collisions")]
     pub use crate::components::collider::ClothCollider;
     pub use crate::{
+        bending::BendingAngle,
         components::{cloth_builder::ClothBuilder, cloth_rendering::NormalComputing},
-        config::{AccelerationSmoothing, ClothConfig},
+        config::{AccelerationSmoothing, ClothConfig, ClothIteration, ClothSolver, ClothTimeStep},
         error::Error,
+        events::{
+            ClothAnchorBroken, ClothAnchorChanged, ClothInitFailed, ClothReady, ClothReset,
+            ClothSettled, ClothTornEvent,
+        },
+        grab::{ClothGrab, ClothGrabConfig},
         mesh::rectangle_mesh,
-        stick::{StickGeneration, StickLen, StickMode},
+        query::{ClothPointProjection, ClothQuery, ClothRayHit},
+        stick::{StickCategory, StickGeneration, StickLen, StickMode},
         vertex_anchor::VertexAnchor,
         wind::{Wind, Winds},
-        ClothPlugin,
+        ClothGrabPlugin, ClothPlugin,
+    };
+    #[cfg(feature = "gizmos")]
+    pub use crate::{
+        debug::{ClothDebugConfig, StickColoring},
+        ClothDebugPlugin,
     };
+    #[cfg(feature = "heatmap")]
+    pub use crate::{heatmap::ClothHeatmapMaterial, ClothHeatmapPlugin};
 }
 
 /// Plugin for cloth physics
@@ -321,26 +745,112 @@ pub struct ClothPlugin;
 
 impl Plugin for ClothPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ClothConfig>();
+        app.init_resource::<ClothConfig>()
+            .init_resource::<systems::cloth::ClothMeshModifications>();
         app.register_type::<ClothConfig>()
             .register_type::<Wind>()
             .register_type::<Winds>()
             .register_type::<ClothBuilder>();
+        app.add_event::<ClothReady>()
+            .add_event::<ClothInitFailed>()
+            .add_event::<ClothReset>()
+            .add_event::<ClothSettled>()
+            .add_event::<ClothTornEvent>()
+            .add_event::<ClothAnchorChanged>()
+            .add_event::<ClothAnchorBroken>();
         app.add_systems(
             Update,
             (
                 systems::cloth::init,
-                (systems::cloth::update, systems::cloth::render).chain(),
-            ),
+                apply_deferred,
+                systems::cloth::update,
+                systems::cloth::tear,
+                systems::cloth::send_events,
+                systems::cloth::render,
+            )
+                .chain(),
         );
         #[cfg(feature = "rapier_collisions")]
         app.register_type::<ClothCollider>().add_systems(
             Update,
             (
                 systems::collisions::init_cloth_collider,
-                systems::collisions::handle_collisions.before(systems::cloth::render),
+                systems::collisions::handle_collisions
+                    .after(systems::cloth::update)
+                    .after(systems::cloth::tear)
+                    .before(systems::cloth::render),
             ),
         );
         bevy::log::info!("Loaded Cloth Plugin");
     }
 }
+
+/// Plugin allowing to grab cloth points with the mouse: the cloth point under
+/// the cursor is temporarily anchored to a drag target following the cursor
+/// (See [`ClothGrabConfig`]).
+///
+/// Note: requires the [`ClothPlugin`]
+#[derive(Copy, Clone, Default)]
+pub struct ClothGrabPlugin;
+
+impl Plugin for ClothGrabPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClothGrabConfig>()
+            .init_resource::<ClothGrab>();
+        app.register_type::<ClothGrabConfig>()
+            .register_type::<ClothGrab>();
+        app.add_systems(
+            Update,
+            (systems::grab::grab, apply_deferred)
+                .chain()
+                .before(systems::cloth::update),
+        );
+    }
+}
+
+/// Plugin drawing cloth debug gizmos: sticks, anchored points, velocities and
+/// collider bounds (See [`ClothDebugConfig`]).
+///
+/// Note: requires the [`ClothPlugin`] and the `gizmos` feature
+#[cfg(feature = "gizmos")]
+#[derive(Copy, Clone, Default)]
+pub struct ClothDebugPlugin;
+
+#[cfg(feature = "gizmos")]
+impl Plugin for ClothDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClothDebugConfig>();
+        app.register_type::<ClothDebugConfig>();
+        app.add_systems(
+            Update,
+            systems::debug::draw_cloths.after(systems::cloth::render),
+        );
+        #[cfg(feature = "rapier_collisions")]
+        app.add_systems(
+            Update,
+            systems::debug::draw_colliders.after(systems::cloth::render),
+        );
+    }
+}
+
+/// Plugin providing the [`ClothHeatmapMaterial`] debug material, displaying the
+/// cloth strain.
+///
+/// Note: requires the `heatmap` feature
+#[cfg(feature = "heatmap")]
+#[derive(Copy, Clone, Default)]
+pub struct ClothHeatmapPlugin;
+
+#[cfg(feature = "heatmap")]
+impl Plugin for ClothHeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        use heatmap::CLOTH_HEATMAP_SHADER_HANDLE;
+        bevy::asset::load_internal_asset!(
+            app,
+            CLOTH_HEATMAP_SHADER_HANDLE,
+            "heatmap.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(MaterialPlugin::<ClothHeatmapMaterial>::default());
+    }
+}
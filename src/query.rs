@@ -0,0 +1,200 @@
+use crate::components::cloth::Cloth;
+use bevy::{
+    ecs::{
+        prelude::{Entity, Query},
+        system::SystemParam,
+    },
+    math::{Ray, Vec3, Vec3A},
+    reflect::Reflect,
+};
+
+/// Intersection of a ray with a cloth surface, see [`Cloth::cast_ray`]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct ClothRayHit {
+    /// The hit cloth triangle index, matching the [`Cloth::triangles`] and
+    /// the `ClothRendering::indices` triangles
+    pub triangle: usize,
+    /// Distance of the hit along the ray
+    pub distance: f32,
+    /// Barycentric coordinates of the hit point in the triangle
+    pub barycentric: Vec3,
+    /// World space hit point
+    pub point: Vec3,
+    /// World space triangle normal, facing the ray origin
+    pub normal: Vec3,
+}
+
+/// Closest point of a cloth surface to a given point, see
+/// [`Cloth::closest_point`]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct ClothPointProjection {
+    /// The closest cloth triangle index, matching the [`Cloth::triangles`]
+    /// and the `ClothRendering::indices` triangles
+    pub triangle: usize,
+    /// Distance between the given point and the closest point
+    pub distance: f32,
+    /// Barycentric coordinates of the closest point in the triangle
+    pub barycentric: Vec3,
+    /// World space closest point
+    pub point: Vec3,
+}
+
+/// System parameter allowing to query every simulated cloth surface
+#[derive(SystemParam)]
+pub struct ClothQuery<'w, 's> {
+    cloths: Query<'w, 's, (Entity, &'static Cloth)>,
+}
+
+impl ClothQuery<'_, '_> {
+    /// Casts a world space `ray` against every cloth, returning the nearest
+    /// hit cloth entity and the hit (See [`Cloth::cast_ray`])
+    #[must_use]
+    pub fn cast_ray(&self, ray: Ray) -> Option<(Entity, ClothRayHit)> {
+        self.cloths
+            .iter()
+            .filter_map(|(entity, cloth)| cloth.cast_ray(ray).map(|hit| (entity, hit)))
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Retrieves the closest cloth surface point to the given world space
+    /// `point`, returning the cloth entity and the projection (See
+    /// [`Cloth::closest_point`])
+    #[must_use]
+    pub fn closest_point(&self, point: Vec3) -> Option<(Entity, ClothPointProjection)> {
+        self.cloths
+            .iter()
+            .filter_map(|(entity, cloth)| cloth.closest_point(point).map(|p| (entity, p)))
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))
+    }
+}
+
+/// Computes the intersection of `ray` with the `[a, b, c]` triangle, if any
+/// (Möller–Trumbore algorithm). Both triangle faces are hit.
+///
+/// Returns the intersection distance along the ray and its barycentric
+/// coordinates
+#[must_use]
+pub fn ray_triangle_intersection(ray: Ray, [a, b, c]: [Vec3A; 3]) -> Option<(f32, Vec3)> {
+    let origin = Vec3A::from(ray.origin);
+    let direction = Vec3A::from(ray.direction);
+    let first_edge = b - a;
+    let second_edge = c - a;
+    let direction_cross = direction.cross(second_edge);
+    let determinant = first_edge.dot(direction_cross);
+    if determinant.abs() <= f32::EPSILON {
+        return None;
+    }
+    let inverse_determinant = determinant.recip();
+    let offset = origin - a;
+    let first_weight = offset.dot(direction_cross) * inverse_determinant;
+    if !(0.0..=1.0).contains(&first_weight) {
+        return None;
+    }
+    let offset_cross = offset.cross(first_edge);
+    let second_weight = direction.dot(offset_cross) * inverse_determinant;
+    if second_weight < 0.0 || first_weight + second_weight > 1.0 {
+        return None;
+    }
+    let distance = second_edge.dot(offset_cross) * inverse_determinant;
+    let barycentric = Vec3::new(
+        1.0 - first_weight - second_weight,
+        first_weight,
+        second_weight,
+    );
+    (distance > 0.0).then_some((distance, barycentric))
+}
+
+/// Computes the barycentric coordinates of the closest point to `point` in the
+/// `[a, b, c]` triangle (From *Real-Time Collision Detection*, Christer
+/// Ericson)
+#[must_use]
+#[allow(clippy::many_single_char_names, clippy::similar_names)]
+pub fn closest_point_on_triangle(point: Vec3A, [a, b, c]: [Vec3A; 3]) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return Vec3::X;
+    }
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return Vec3::Y;
+    }
+    let vc = d1.mul_add(d4, -(d3 * d2));
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return Vec3::new(1.0 - v, v, 0.0);
+    }
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return Vec3::Z;
+    }
+    let vb = d5.mul_add(d2, -(d1 * d6));
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return Vec3::new(1.0 - w, 0.0, w);
+    }
+    let va = d3.mul_add(d6, -(d5 * d4));
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return Vec3::new(0.0, 1.0 - w, w);
+    }
+    let sum = va + vb + vc;
+    if sum.abs() <= f32::EPSILON {
+        // Degenerate triangle
+        return Vec3::X;
+    }
+    let v = vb / sum;
+    let w = vc / sum;
+    Vec3::new(1.0 - v - w, v, w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_hits_triangle() {
+        let triangle = [Vec3A::ZERO, Vec3A::X, Vec3A::Y];
+        let ray = Ray {
+            origin: Vec3::new(0.25, 0.5, 2.0),
+            direction: Vec3::NEG_Z,
+        };
+        assert_eq!(
+            ray_triangle_intersection(ray, triangle),
+            Some((2.0, Vec3::new(0.25, 0.25, 0.5)))
+        );
+        let ray = Ray {
+            origin: Vec3::new(1.0, 1.0, 2.0),
+            direction: Vec3::NEG_Z,
+        };
+        assert_eq!(ray_triangle_intersection(ray, triangle), None);
+        let ray = Ray {
+            origin: Vec3::new(0.25, 0.25, 2.0),
+            direction: Vec3::Z,
+        };
+        assert_eq!(ray_triangle_intersection(ray, triangle), None);
+    }
+
+    #[test]
+    fn closest_point_in_triangle_regions() {
+        let triangle = [Vec3A::ZERO, Vec3A::X, Vec3A::Y];
+        let closest = |point: Vec3A| closest_point_on_triangle(point, triangle);
+        assert_eq!(closest(Vec3A::new(-1.0, -1.0, 0.0)), Vec3::X);
+        assert_eq!(
+            closest(Vec3A::new(0.5, -1.0, 0.0)),
+            Vec3::new(0.5, 0.5, 0.0)
+        );
+        assert_eq!(closest(Vec3A::new(2.0, 2.0, 0.0)), Vec3::new(0.0, 0.5, 0.5));
+        assert_eq!(
+            closest(Vec3A::new(0.25, 0.25, 1.0)),
+            Vec3::new(0.5, 0.25, 0.25)
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::{
+    math::{IVec3, Vec3A},
+    utils::HashMap,
+};
+
+/// Spatial hash grid of cloth points, used to find the close points
+/// candidate for self collision
+#[derive(Debug, Clone, Default)]
+pub struct SpatialHash {
+    /// Size of a grid cell
+    pub cell_size: f32,
+    /// Point ids for every non empty grid cell
+    cells: HashMap<IVec3, Vec<usize>>,
+}
+
+impl SpatialHash {
+    /// Creates an empty spatial hash
+    ///
+    /// # Arguments
+    ///
+    /// * `cell_size` - The size of a grid cell, should be at least the
+    ///   searched distance
+    #[must_use]
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn cell(&self, position: Vec3A) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Clears the grid and inserts every point of `positions`. Cell
+    /// allocations are kept between rebuilds.
+    pub fn rebuild(&mut self, positions: &[Vec3A]) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+        for (i, position) in positions.iter().enumerate() {
+            let cell = self.cell(*position);
+            self.cells.entry(cell).or_default().push(i);
+        }
+    }
+
+    /// Retrieves the ids of the points in the cell of `position` and its
+    /// neighbouring cells
+    pub fn neighbours(&self, position: Vec3A) -> impl Iterator<Item = usize> + '_ {
+        let center = self.cell(position);
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| IVec3::new(x, y, z))))
+            .filter_map(move |offset| self.cells.get(&(center + offset)))
+            .flatten()
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_neighbours() {
+        let mut hash = SpatialHash::new(1.0);
+        hash.rebuild(&[
+            Vec3A::ZERO,
+            Vec3A::splat(0.9),
+            Vec3A::X * 3.0,
+            -Vec3A::Y * 0.5,
+        ]);
+        let mut neighbours: Vec<_> = hash.neighbours(Vec3A::ZERO).collect();
+        neighbours.sort_unstable();
+        assert_eq!(neighbours, vec![0, 1, 3]);
+    }
+}
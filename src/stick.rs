@@ -1,4 +1,5 @@
-use bevy::{math::Vec3, reflect::Reflect};
+use crate::components::cloth::StickId;
+use bevy::{math::Vec3, reflect::Reflect, utils::HashMap};
 
 /// Defines how the cloth will compute sticks from mesh indices.
 #[derive(Debug, Copy, Clone, Default, Reflect, PartialEq, Eq)]
@@ -8,6 +9,25 @@ pub enum StickGeneration {
     Quads,
     /// 3 sticks will be generated by triangle
     Triangles,
+    /// Classic Provot mass-spring model generated from the mesh topology:
+    /// - [`StickCategory::Structural`] sticks follow the actual quad edges
+    /// - [`StickCategory::Shear`] sticks follow both diagonals of every quad
+    /// - [`StickCategory::Bend`] sticks connect every point to the point two
+    ///   structural sticks away, skipping the one in between
+    MassSpring,
+}
+
+/// Defines the category of a cloth stick, allowing to customize the
+/// behaviour of each category (See [`StickGeneration`])
+#[derive(Debug, Copy, Clone, Default, Reflect, PartialEq, Eq, Hash)]
+pub enum StickCategory {
+    #[default]
+    /// Sticks following the actual quad edges, resisting stretch
+    Structural,
+    /// Sticks following quad diagonals, resisting shearing
+    Shear,
+    /// Sticks skipping one point, resisting bending
+    Bend,
 }
 
 /// Defines the target length of cloth sticks
@@ -24,14 +44,20 @@ pub enum StickLen {
     Coefficient(f32),
 }
 
-/// Defines cloth stick behaviour
-#[derive(Debug, Copy, Clone, Default, Reflect)]
+/// Defines cloth stick behaviour.
+///
+/// Every mode has an optional `tear_threshold`: a stick stretched above its
+/// expected length multiplied by this value will tear, splitting the cloth
+/// (`1.5` means the stick tears when stretched above 150% of its length).
+#[derive(Debug, Copy, Clone, Reflect)]
 pub enum StickMode {
     /// The stick will attempt to always remain at the same length (See
     /// [`StickLen`]). This is the default behaviour and the fastest to
     /// compute.
-    #[default]
-    Fixed,
+    Fixed {
+        /// Optional tearing threshold of the stick
+        tear_threshold: Option<f32>,
+    },
     /// The stick will clamp its length between a `min_percent` and
     /// `max_percent` of its expected length (See [`StickLen`]).
     ///
@@ -41,6 +67,7 @@ pub enum StickMode {
     /// let mode = StickMode::Spring {
     ///     min_percent: 0.0,
     ///     max_percent: 1.0,
+    ///     tear_threshold: None,
     /// };
     /// ```
     /// will behave like a [`StickMode::Fixed`].
@@ -48,9 +75,9 @@ pub enum StickMode {
     /// # Notes
     ///
     /// * Please note that this mode is slower, as some distance computing
-    ///   involving square roots,
-    /// will happen every frame. If you just want to have smaller or larger
-    /// sticks, prefer setting a different [`StickLen`] instead
+    ///   involving square roots, will happen every frame. If you just want to
+    ///   have smaller or larger sticks, prefer setting a different
+    ///   [`StickLen`] instead
     /// * Setting invalid `min_percent` and `max_percent` will result in
     ///   unexpected behaviour:
     ///   - max value being lower than the min value
@@ -68,7 +95,122 @@ pub enum StickMode {
         /// The stick will attempt to be at most this percent of its expected
         /// length.
         max_percent: f32,
+        /// Optional tearing threshold of the stick
+        tear_threshold: Option<f32>,
     },
+    /// The stick will attempt to remain at its expected length (See
+    /// [`StickLen`]) with a given `compliance`, the inverse of its stiffness.
+    ///
+    /// This mode is meant to be used with the [`ClothSolver::Xpbd`] solver,
+    /// which keeps the stick stiffness consistent whatever the sticks
+    /// computation depth or the frame rate. With the default
+    /// [`ClothSolver::Projection`] solver it behaves like a
+    /// [`StickMode::Fixed`].
+    ///
+    /// # Notes
+    ///
+    /// * A compliance of `0.0` means an infinitely stiff stick
+    /// * The compliance is expressed in meters per Newton, meaningful values
+    ///   are usually very small (`1e-3` for a soft fabric, `1e-7` for a stiff
+    ///   one)
+    ///
+    /// [`ClothSolver::Xpbd`]: crate::config::ClothSolver::Xpbd
+    /// [`ClothSolver::Projection`]: crate::config::ClothSolver::Projection
+    Compliant {
+        /// The stick compliance, the inverse of its stiffness
+        compliance: f32,
+        /// Optional tearing threshold of the stick
+        tear_threshold: Option<f32>,
+    },
+}
+
+impl Default for StickMode {
+    fn default() -> Self {
+        Self::Fixed {
+            tear_threshold: None,
+        }
+    }
+}
+
+impl StickGeneration {
+    /// Minimum cosine between two structural sticks of a point for them to be
+    /// considered aligned, and generate a [`StickCategory::Bend`] stick.
+    const BEND_ALIGNMENT: f32 = -0.9;
+
+    /// Generates the sticks and their category from the mesh `indices`
+    /// (triangles)
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - The rest positions of the points
+    /// * `indices` - The mesh indices
+    #[must_use]
+    pub fn generate_sticks(
+        &self,
+        positions: &[Vec3],
+        indices: &[usize],
+    ) -> HashMap<StickId, StickCategory> {
+        fn insert(sticks: &mut HashMap<StickId, StickCategory>, [a, b]: StickId, c: StickCategory) {
+            if !sticks.contains_key(&[b, a]) {
+                sticks.entry([a, b]).or_insert(c);
+            }
+        }
+
+        let mut sticks = HashMap::with_capacity(indices.len());
+        let mut diagonals: HashMap<StickId, Vec<usize>> = HashMap::new();
+        for truple in indices.chunks_exact(3) {
+            let [a, b, c] = [truple[0], truple[1], truple[2]];
+            insert(&mut sticks, [a, b], StickCategory::Structural);
+            insert(&mut sticks, [b, c], StickCategory::Structural);
+            match self {
+                Self::Quads => (),
+                Self::Triangles => insert(&mut sticks, [c, a], StickCategory::Shear),
+                Self::MassSpring => {
+                    insert(&mut sticks, [c, a], StickCategory::Shear);
+                    diagonals.entry([a.min(c), a.max(c)]).or_default().push(b);
+                }
+            }
+        }
+        if *self != Self::MassSpring {
+            return sticks;
+        }
+        // The opposite diagonal of each quad
+        for opposites in diagonals.values() {
+            if let [a, b] = opposites[..] {
+                insert(&mut sticks, [a, b], StickCategory::Shear);
+            }
+        }
+        // Skip-one sticks along aligned structural sticks
+        let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+        for ([a, b], _) in sticks
+            .iter()
+            .filter(|(_, c)| **c == StickCategory::Structural)
+        {
+            neighbours.entry(*a).or_default().push(*b);
+            neighbours.entry(*b).or_default().push(*a);
+        }
+        let mut bend_sticks = Vec::new();
+        for (point, neighbours) in &neighbours {
+            let direction = |i: usize| (positions[i] - positions[*point]).normalize_or_zero();
+            for a in neighbours {
+                let dir_a = direction(*a);
+                let opposite = neighbours
+                    .iter()
+                    .filter(|c| *c != a)
+                    .map(|c| (*c, dir_a.dot(direction(*c))))
+                    .min_by(|(_, x), (_, y)| x.total_cmp(y));
+                if let Some((c, cos)) = opposite {
+                    if cos < Self::BEND_ALIGNMENT {
+                        bend_sticks.push([*a, c]);
+                    }
+                }
+            }
+        }
+        for id in bend_sticks {
+            insert(&mut sticks, id, StickCategory::Bend);
+        }
+        sticks
+    }
 }
 
 impl StickLen {
@@ -84,11 +226,62 @@ impl StickLen {
     }
 }
 
+impl StickMode {
+    /// Retrieves the length the stick should be constrained to, from the
+    /// current `distance` between the two points it connects and its expected
+    /// `target_len`.
+    ///
+    /// Returns `None` if the stick doesn't require to be constrained
+    #[must_use]
+    pub fn constrained_len(&self, distance: f32, target_len: f32) -> Option<f32> {
+        match self {
+            Self::Fixed { .. } | Self::Compliant { .. } => Some(target_len),
+            Self::Spring {
+                min_percent,
+                max_percent,
+                ..
+            } => {
+                let dist = distance / target_len;
+                if dist < *min_percent {
+                    Some(target_len * min_percent)
+                } else if dist > *max_percent {
+                    Some(target_len * max_percent)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Retrieves the stick compliance, only [`StickMode::Compliant`] sticks
+    /// have a non zero compliance.
+    #[inline]
+    #[must_use]
+    pub const fn compliance(&self) -> f32 {
+        match self {
+            Self::Fixed { .. } | Self::Spring { .. } => 0.0,
+            Self::Compliant { compliance, .. } => *compliance,
+        }
+    }
+
+    /// Retrieves the stick tearing threshold, if any
+    #[inline]
+    #[must_use]
+    pub const fn tear_threshold(&self) -> Option<f32> {
+        match self {
+            Self::Fixed { tear_threshold }
+            | Self::Spring { tear_threshold, .. }
+            | Self::Compliant { tear_threshold, .. } => *tear_threshold,
+        }
+    }
+}
+
 impl From<[f32; 2]> for StickMode {
     fn from([min, max]: [f32; 2]) -> Self {
         Self::Spring {
             min_percent: min,
             max_percent: max,
+            tear_threshold: None,
         }
     }
 }
@@ -98,6 +291,189 @@ impl From<(f32, f32)> for StickMode {
         Self::Spring {
             min_percent: min,
             max_percent: max,
+            tear_threshold: None,
+        }
+    }
+}
+
+/// Cloth sticks, stored as contiguous arrays sharing the same stick index
+/// for cache friendly constraint solving.
+///
+/// Note: Removing a stick moves the last stick to its index
+#[derive(Debug, Clone, Default)]
+pub struct Sticks {
+    /// The two connected point ids of every stick
+    ids: Vec<StickId>,
+    /// The target length of every stick
+    lengths: Vec<f32>,
+    /// The behaviour mode of every stick
+    modes: Vec<StickMode>,
+    /// The category of every stick
+    categories: Vec<StickCategory>,
+    /// The stick index of every stick id
+    indexes: HashMap<StickId, usize>,
+}
+
+impl Sticks {
+    /// Retrieves the amount of sticks
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if there are no sticks
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Retrieves the index of the stick connecting the two given points, in
+    /// any order
+    #[must_use]
+    pub fn index(&self, [a, b]: StickId) -> Option<usize> {
+        self.indexes
+            .get(&[a, b])
+            .or_else(|| self.indexes.get(&[b, a]))
+            .copied()
+    }
+
+    /// Returns `true` if a stick connects the two given points, in any order
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, id: StickId) -> bool {
+        self.index(id).is_some()
+    }
+
+    /// Retrieves the connected point ids of every stick
+    #[inline]
+    #[must_use]
+    pub fn ids(&self) -> &[StickId] {
+        &self.ids
+    }
+
+    /// Retrieves the target length of every stick
+    #[inline]
+    #[must_use]
+    pub fn lengths(&self) -> &[f32] {
+        &self.lengths
+    }
+
+    /// Retrieves the mutable target length of every stick
+    #[inline]
+    #[must_use]
+    pub fn lengths_mut(&mut self) -> &mut [f32] {
+        &mut self.lengths
+    }
+
+    /// Retrieves the behaviour mode of every stick
+    #[inline]
+    #[must_use]
+    pub fn modes(&self) -> &[StickMode] {
+        &self.modes
+    }
+
+    /// Retrieves the mutable behaviour mode of every stick
+    #[inline]
+    #[must_use]
+    pub fn modes_mut(&mut self) -> &mut [StickMode] {
+        &mut self.modes
+    }
+
+    /// Retrieves the category of every stick
+    #[inline]
+    #[must_use]
+    pub fn categories(&self) -> &[StickCategory] {
+        &self.categories
+    }
+
+    /// Retrieves the target length of the given stick
+    #[must_use]
+    pub fn length(&self, id: StickId) -> Option<f32> {
+        self.index(id).map(|i| self.lengths[i])
+    }
+
+    /// Retrieves the behaviour mode of the given stick
+    #[must_use]
+    pub fn mode(&self, id: StickId) -> Option<StickMode> {
+        self.index(id).map(|i| self.modes[i])
+    }
+
+    /// Retrieves the category of the given stick
+    #[must_use]
+    pub fn category(&self, id: StickId) -> Option<StickCategory> {
+        self.index(id).map(|i| self.categories[i])
+    }
+
+    /// Inserts a new stick, or updates the existing stick connecting the
+    /// same points. Returns the stick index
+    pub fn insert(
+        &mut self,
+        id: StickId,
+        length: f32,
+        mode: StickMode,
+        category: StickCategory,
+    ) -> usize {
+        if let Some(index) = self.index(id) {
+            self.lengths[index] = length;
+            self.modes[index] = mode;
+            self.categories[index] = category;
+            return index;
+        }
+        let index = self.ids.len();
+        self.ids.push(id);
+        self.lengths.push(length);
+        self.modes.push(mode);
+        self.categories.push(category);
+        self.indexes.insert(id, index);
+        index
+    }
+
+    /// Removes the stick connecting the two given points, in any order. The
+    /// last stick is moved to its index.
+    ///
+    /// Returns `false` if the stick doesn't exist
+    pub fn remove(&mut self, id: StickId) -> bool {
+        let Some(index) = self.index(id) else {
+            return false;
+        };
+        self.indexes.remove(&self.ids[index]);
+        self.ids.swap_remove(index);
+        self.lengths.swap_remove(index);
+        self.modes.swap_remove(index);
+        self.categories.swap_remove(index);
+        if let Some(moved) = self.ids.get(index) {
+            self.indexes.insert(*moved, index);
+        }
+        true
+    }
+
+    /// Changes the connected point ids of the stick at `index`
+    pub fn set_id(&mut self, index: usize, id: StickId) {
+        let Some(old_id) = self.ids.get_mut(index) else {
+            return;
+        };
+        self.indexes.remove(old_id);
+        *old_id = id;
+        self.indexes.insert(id, index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removal_keeps_indexes() {
+        let mut sticks = Sticks::default();
+        for (id, len) in [([0, 1], 0.0), ([1, 2], 1.0), ([2, 3], 2.0)] {
+            sticks.insert(id, len, StickMode::default(), StickCategory::Structural);
         }
+        assert!(sticks.remove([1, 0]));
+        assert!(!sticks.remove([0, 1]));
+        assert_eq!(sticks.ids(), &[[2, 3], [1, 2]]);
+        assert_eq!(sticks.length([3, 2]), Some(2.0));
+        assert_eq!(sticks.index([1, 2]), Some(1));
     }
 }
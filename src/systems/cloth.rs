@@ -1,14 +1,112 @@
 #![allow(
     clippy::needless_pass_by_value,
     clippy::type_complexity,
-    clippy::option_if_let_else
+    clippy::option_if_let_else,
+    clippy::too_many_arguments
 )]
 use crate::{
     components::{cloth::Cloth, cloth_builder::ClothBuilder, cloth_rendering::ClothRendering},
-    config::ClothConfig,
+    config::{ClothConfig, ClothIteration, ClothSolver, ClothTimeStep},
+    error::Error,
+    events::{
+        ClothAnchorBroken, ClothAnchorChanged, ClothInitFailed, ClothReady, ClothReset,
+        ClothSettled, ClothTornEvent,
+    },
     wind::Winds,
 };
-use bevy::{log, math::Vec3, prelude::*, render::primitives::Aabb};
+use bevy::{
+    asset::HandleId,
+    log,
+    math::Vec3,
+    prelude::*,
+    render::primitives::Aabb,
+    tasks::{ComputeTaskPool, TaskPool},
+    utils::{HashMap, HashSet},
+};
+
+/// Amount of mesh modifications made by the cloth rendering and not yet read
+/// as `AssetEvent::Modified` events, per mesh. Allows to only rebuild the
+/// cloths whose mesh was modified by another source.
+#[derive(Debug, Default, Resource)]
+pub struct ClothMeshModifications(HashMap<HandleId, u32>);
+
+impl ClothMeshModifications {
+    /// Records a modification of `mesh` made by the cloth rendering
+    fn record(&mut self, mesh: HandleId) {
+        *self.0.entry(mesh).or_default() += 1;
+    }
+
+    /// Consumes a modification event of `mesh`, returning `true` if it was
+    /// not caused by the cloth rendering
+    fn is_external(&mut self, mesh: HandleId) -> bool {
+        let Some(count) = self.0.get_mut(&mesh) else {
+            return true;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.0.remove(&mesh);
+        }
+        false
+    }
+}
+
+fn simulate<'a>(
+    cloth: &mut Cloth,
+    config: &ClothConfig,
+    transform: &GlobalTransform,
+    anchor_query: impl Fn(Entity) -> Option<&'a GlobalTransform>,
+    wind_velocity: Vec3,
+    delta_time: f32,
+    task_pool: &TaskPool,
+) {
+    cloth.update_wind_velocity(wind_velocity);
+    if cloth.is_sleeping() {
+        if config.sleeping_enabled() {
+            // Sleeping cloths only follow their anchors, waking up if they moved
+            cloth.update_anchored_points(transform, &anchor_query, delta_time);
+        } else {
+            cloth.wake_up();
+        }
+        if cloth.is_sleeping() {
+            return;
+        }
+    }
+    if config.aerodynamics_enabled() {
+        cloth.update_points(
+            config.friction_coefficient(),
+            config.smoothed_acceleration(config.gravity, delta_time),
+        );
+        cloth.update_aerodynamics(
+            wind_velocity,
+            config.drag_coefficient,
+            config.lift_coefficient,
+            delta_time,
+            config.smooth_value(delta_time),
+        );
+    } else {
+        cloth.update_points(
+            config.friction_coefficient(),
+            config.smoothed_acceleration(wind_velocity + config.gravity, delta_time),
+        );
+    }
+    cloth.update_anchored_points(transform, anchor_query, delta_time);
+    match (config.solver, config.iteration) {
+        (ClothSolver::Projection, ClothIteration::GaussSeidel) => {
+            cloth.update_sticks(config.sticks_computation_depth, task_pool);
+        }
+        (ClothSolver::Projection, ClothIteration::Jacobi) => {
+            cloth.update_sticks_jacobi(config.sticks_computation_depth, task_pool);
+        }
+        (ClothSolver::Xpbd, _) => {
+            cloth.update_sticks_xpbd(config.sticks_computation_depth, delta_time, task_pool);
+        }
+    }
+    cloth.update_bending(config.sticks_computation_depth);
+    cloth.update_volume(config.sticks_computation_depth);
+    cloth.update_self_collisions();
+    cloth.update_tethers();
+    cloth.break_overstretched_anchors();
+}
 
 pub fn update(
     mut query: Query<(&mut Cloth, &GlobalTransform, Option<&ClothConfig>)>,
@@ -18,22 +116,134 @@ pub fn update(
     time: Res<Time>,
 ) {
     let delta_time = time.delta_seconds();
-    let wind_force = wind.map_or(Vec3::ZERO, |w| w.current_velocity(time.elapsed_seconds()));
+    let task_pool = ComputeTaskPool::get();
+    let wind_velocity = |elapsed: f32| {
+        wind.as_ref()
+            .map_or(Vec3::ZERO, |w| w.current_velocity(elapsed))
+    };
+    let wind_force = wind_velocity(time.elapsed_seconds());
+    let anchor_query = |entity| {
+        if let Ok(t) = anchor_query.get(entity) {
+            Some(t)
+        } else {
+            log::error!("Could not find cloth anchor target entity {:?}", entity);
+            None
+        }
+    };
     for (mut cloth, transform, custom_config) in &mut query {
         let config: &ClothConfig = custom_config.unwrap_or(&config);
-        cloth.update_points(
-            config.friction_coefficient(),
-            config.smoothed_acceleration(wind_force + config.gravity, delta_time),
-        );
-        cloth.update_anchored_points(transform, |entity| {
-            if let Ok(t) = anchor_query.get(entity) {
-                Some(t)
+        cloth.begin_frame();
+        let time_step = config.effective_time_step();
+        let Some(step_duration) = time_step.step_duration() else {
+            simulate(
+                &mut cloth,
+                config,
+                transform,
+                anchor_query,
+                wind_force,
+                delta_time,
+                task_pool,
+            );
+            if config.sleeping_enabled() {
+                cloth.update_sleep(config.sleep_threshold, config.sleep_steps);
+            }
+            continue;
+        };
+        let substeps = time_step.substeps();
+        let substep_duration = step_duration / f32::from(substeps);
+        cloth.time_accumulator += delta_time;
+        let available_steps = (cloth.time_accumulator / step_duration).floor();
+        let steps = available_steps.min(f32::from(ClothTimeStep::MAX_STEPS_PER_FRAME));
+        // Steps above the limit are dropped, or delayed in deterministic mode
+        cloth.time_accumulator -= if config.deterministic {
+            steps
+        } else {
+            available_steps
+        } * step_duration;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        for _ in 0..steps as u8 {
+            let step_wind_force = if config.deterministic {
+                #[allow(clippy::cast_precision_loss)]
+                let elapsed = cloth.step_count as f32 * step_duration;
+                wind_velocity(elapsed)
             } else {
-                log::error!("Could not find cloth anchor target entity {:?}", entity);
-                None
+                wind_force
+            };
+            cloth.begin_fixed_step();
+            for _ in 0..substeps {
+                simulate(
+                    &mut cloth,
+                    config,
+                    transform,
+                    anchor_query,
+                    step_wind_force,
+                    substep_duration,
+                    task_pool,
+                );
             }
+            // Rest steps are counted once per fixed step, from the displacement
+            // of its last substep
+            if config.sleeping_enabled() {
+                cloth.update_sleep(
+                    config.sleep_threshold / f32::from(substeps),
+                    config.sleep_steps,
+                );
+            }
+        }
+    }
+}
+
+pub fn tear(
+    mut query: Query<(Entity, &mut Cloth, &mut ClothRendering)>,
+    mut tear_events: EventWriter<ClothTornEvent>,
+) {
+    for (entity, mut cloth, mut rendering) in &mut query {
+        if cloth.is_tearable() && !cloth.is_sleeping() {
+            cloth.tear_overstretched_sticks();
+        }
+        let tears = cloth.drain_tears();
+        if tears.is_empty() {
+            continue;
+        }
+        for split in tears.iter().flat_map(|t| &t.splits) {
+            rendering.split_vertex(split);
+        }
+        tear_events.send(ClothTornEvent {
+            entity,
+            sticks: tears.iter().map(|t| t.stick).collect(),
+            triangles: tears.into_iter().flat_map(|t| t.triangles).collect(),
         });
-        cloth.update_sticks(config.sticks_computation_depth);
+    }
+}
+
+pub fn send_events(
+    mut query: Query<(Entity, &mut Cloth)>,
+    mut anchor_events: EventWriter<ClothAnchorChanged>,
+    mut break_events: EventWriter<ClothAnchorBroken>,
+    mut settled_events: EventWriter<ClothSettled>,
+) {
+    for (entity, mut cloth) in &mut query {
+        if cloth.take_settled() {
+            settled_events.send(ClothSettled { entity });
+        }
+        break_events.send_batch(
+            cloth
+                .drain_anchor_breaks()
+                .into_iter()
+                .map(|(point, anchor)| ClothAnchorBroken {
+                    entity,
+                    point,
+                    anchor,
+                }),
+        );
+        anchor_events.send_batch(cloth.drain_anchor_changes().into_iter().map(|change| {
+            ClothAnchorChanged {
+                entity,
+                point: change.point,
+                previous: change.previous,
+                anchor: change.anchor,
+            }
+        }));
     }
 }
 
@@ -44,13 +254,45 @@ pub fn render(
         &mut Aabb,
         &GlobalTransform,
         &Handle<Mesh>,
+        Option<&ClothConfig>,
     )>,
+    config: Res<ClothConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut mesh_modifications: ResMut<ClothMeshModifications>,
+    time: Res<Time>,
 ) {
-    for (cloth, mut rendering, mut aabb, transform, handle) in &mut cloth_query {
+    for (cloth, mut rendering, mut aabb, transform, handle, custom_config) in &mut cloth_query {
+        // The mesh of a sleeping cloth is already up to date
+        if cloth.is_sleeping() && rendering.sleeping {
+            continue;
+        }
+        let config: &ClothConfig = custom_config.unwrap_or(&config);
+        // `Assets::get_mut` sends a modification event even for missing meshes
+        mesh_modifications.record(handle.id());
         if let Some(mesh) = meshes.get_mut(handle) {
-            rendering.update_positions(cloth.compute_vertex_positions(transform));
+            let time_step = config.effective_time_step();
+            if rendering.vertex_strains.is_some() {
+                rendering.update_strains(&cloth.compute_point_strains());
+            }
+            if rendering.vertex_velocities.is_some() {
+                // Point velocities are computed over the last (sub)step
+                let delta_time = time_step.step_duration().map_or_else(
+                    || time.delta_seconds(),
+                    |d| d / f32::from(time_step.substeps()),
+                );
+                rendering.update_velocities(cloth.compute_vertex_velocities(transform, delta_time));
+            }
+            if let Some(step_duration) = time_step.step_duration() {
+                let alpha = (cloth.time_accumulator / step_duration).clamp(0.0, 1.0);
+                rendering.update_positions(
+                    cloth.compute_interpolated_vertex_positions(transform, alpha),
+                );
+            } else {
+                rendering.update_positions(cloth.compute_vertex_positions(transform));
+            }
             rendering.apply(mesh);
+            rendering.mark_applied();
+            rendering.sleeping = cloth.is_sleeping();
             *aabb = rendering.compute_aabb();
         } else {
             log::warn!("A Cloth has a `ClothRendering` component without a loaded mesh handle");
@@ -58,27 +300,172 @@ pub fn render(
     }
 }
 
+/// Copies the `mesh` data used to build a cloth: its indices and its position,
+/// UV, color and normal attributes
+fn source_mesh(mesh: &Mesh) -> Mesh {
+    let mut source = Mesh::new(mesh.primitive_topology());
+    for attribute in [
+        Mesh::ATTRIBUTE_POSITION,
+        Mesh::ATTRIBUTE_UV_0,
+        Mesh::ATTRIBUTE_COLOR,
+        Mesh::ATTRIBUTE_NORMAL,
+    ] {
+        if let Some(values) = mesh.attribute(attribute.id) {
+            source.insert_attribute(attribute, values.clone());
+        }
+    }
+    source.set_indices(mesh.indices().cloned());
+    source
+}
+
+fn build_cloth(
+    builder: &ClothBuilder,
+    mesh: &Mesh,
+    transform: &GlobalTransform,
+) -> Result<(ClothRendering, Cloth), Error> {
+    let matrix = transform.compute_matrix();
+    let mut rendering = ClothRendering::init(mesh, builder.normals_computing)?;
+    let anchored_vertex_ids = builder.anchored_vertex_ids(mesh);
+    let vertex_count = rendering.vertex_positions.len();
+    rendering.source_mesh = Some(source_mesh(mesh));
+    rendering.vertex_strains = builder.strain_attribute.then(|| vec![0.0; vertex_count]);
+    rendering.vertex_velocities = builder
+        .velocity_attribute
+        .then(|| vec![Vec3::ZERO; vertex_count]);
+    if let Some(id) = anchored_vertex_ids.keys().find(|id| **id >= vertex_count) {
+        return Err(Error::AnchoredVertexOutOfBounds(*id));
+    }
+    let mut cloth = Cloth::new(
+        &rendering.vertex_positions,
+        &rendering.indices,
+        anchored_vertex_ids,
+        builder.stick_generation,
+        builder.stick_length,
+        builder.default_stick_mode,
+        &matrix,
+    );
+    for (category, stick_len) in &builder.category_stick_lengths {
+        cloth.edit_category_stick_lengths(*category, *stick_len);
+    }
+    for (vertex_id, mass) in &builder.vertex_masses {
+        cloth.set_point_mass(*vertex_id, *mass);
+    }
+    // The builder masses are part of the initial state
+    cloth.clear_edits();
+    for (category, stick_mode) in &builder.category_stick_modes {
+        cloth.edit_category_stick_modes(*category, *stick_mode);
+    }
+    if let Some(stretch) = builder.tether_stretch {
+        cloth.tether_stretch = Some(stretch);
+        cloth.generate_tethers();
+    }
+    if let Some(target_ratio) = builder.volume_target_ratio {
+        cloth.generate_volume_constraint(&rendering.indices, target_ratio, builder.volume_pressure);
+    }
+    if let Some(thickness) = builder.self_collision_thickness {
+        cloth.set_self_collision(thickness);
+    }
+    if builder.bending_stiffness > 0.0 {
+        cloth.generate_bending_constraints(
+            &rendering.indices,
+            builder.bending_angle,
+            builder.bending_stiffness,
+        );
+    }
+    Ok((rendering, cloth))
+}
+
 pub fn init(
     mut commands: Commands,
-    mut query: Query<(Entity, &ClothBuilder, &GlobalTransform, &Handle<Mesh>), Added<ClothBuilder>>,
+    changed_query: Query<Entity, Or<(Changed<ClothBuilder>, Changed<Handle<Mesh>>)>>,
+    query: Query<(
+        Entity,
+        Ref<ClothBuilder>,
+        &GlobalTransform,
+        Ref<Handle<Mesh>>,
+        Option<&Cloth>,
+        Option<&ClothRendering>,
+    )>,
     meshes: Res<Assets<Mesh>>,
+    mut mesh_modifications: ResMut<ClothMeshModifications>,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut ready_events: EventWriter<ClothReady>,
+    mut reset_events: EventWriter<ClothReset>,
+    mut failure_events: EventWriter<ClothInitFailed>,
 ) {
-    for (entity, builder, transform, handle) in &mut query {
-        if let Some(mesh) = meshes.get(handle) {
-            let matrix = transform.compute_matrix();
-            log::debug!("Initializing Cloth entity {:?}", entity);
-            let rendering = ClothRendering::init(mesh, builder.normals_computing).unwrap();
-            let aabb = rendering.compute_aabb();
-            let cloth = Cloth::new(
-                &rendering.vertex_positions,
-                &rendering.indices,
-                builder.anchored_vertex_ids(mesh),
-                builder.stick_generation,
-                builder.stick_length,
-                builder.default_stick_mode,
-                &matrix,
-            );
-            commands.entity(entity).insert((rendering, cloth, aabb));
+    let modified_meshes: HashSet<HandleId> = mesh_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } => Some(handle.id()),
+            // The cloth rendering itself modifies the mesh every frame
+            AssetEvent::Modified { handle } => mesh_modifications
+                .is_external(handle.id())
+                .then(|| handle.id()),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+    // Cloths with unchanged components are only rebuilt if their mesh was modified
+    let modified_cloths = (!modified_meshes.is_empty())
+        .then(|| {
+            query.iter().filter(|(_, builder, _, handle, ..)| {
+                !builder.is_changed()
+                    && !handle.is_changed()
+                    && modified_meshes.contains(&handle.id())
+            })
+        })
+        .into_iter()
+        .flatten();
+    for (entity, builder, transform, handle, previous_cloth, previous_rendering) in
+        query.iter_many(&changed_query).chain(modified_cloths)
+    {
+        let Some(mesh) = meshes.get(&*handle) else {
+            continue;
+        };
+        let rebuild_from_source =
+            !builder.is_added() && !handle.is_changed() && !modified_meshes.contains(&handle.id());
+        // The cloth is rebuilt from its original mesh when only its builder changed
+        let source_mesh = previous_rendering
+            .and_then(|r| r.source_mesh.as_ref())
+            .filter(|_| rebuild_from_source);
+        log::debug!("Initializing Cloth entity {:?}", entity);
+        match build_cloth(&builder, source_mesh.unwrap_or(mesh), transform) {
+            Ok((mut rendering, mut cloth)) => {
+                let aabb = rendering.compute_aabb();
+                if source_mesh.is_some() {
+                    // The mesh may still hold the torn topology
+                    rendering.topology_changed = true;
+                }
+                if let Some(previous_cloth) = previous_cloth {
+                    cloth.copy_state_from(previous_cloth);
+                }
+                commands.entity(entity).insert((rendering, cloth, aabb));
+                if previous_cloth.is_some() {
+                    reset_events.send(ClothReset { entity });
+                } else {
+                    ready_events.send(ClothReady { entity });
+                }
+            }
+            Err(error) => {
+                log::error!("Failed to initialize Cloth entity {:?}: {}", entity, error);
+                failure_events.send(ClothInitFailed { entity, error });
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_rendering_mesh_modifications() {
+        let [mesh, other] = [HandleId::random::<Mesh>(), HandleId::random::<Mesh>()];
+        let mut modifications = ClothMeshModifications::default();
+        modifications.record(mesh);
+        modifications.record(mesh);
+        assert!(!modifications.is_external(mesh));
+        assert!(!modifications.is_external(mesh));
+        assert!(modifications.is_external(mesh));
+        assert!(modifications.is_external(other));
+    }
+}
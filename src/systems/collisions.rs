@@ -9,7 +9,7 @@ use bevy::{log, prelude::*, render::primitives::Aabb};
 use bevy_rapier3d::prelude::*;
 
 fn get_collider(aabb: &Aabb, collider: &ClothCollider) -> Collider {
-    let extents = aabb.half_extents + collider.offset;
+    let extents = collider.half_extents(aabb);
     Collider::compound(vec![(
         aabb.center.into(),
         Quat::IDENTITY,
@@ -28,12 +28,39 @@ pub fn handle_collisions(
 ) {
     let delta_time = time.delta_seconds();
     for (entity, mut cloth, aabb, collider, mut rapier_collider) in &mut cloth_query {
-        for contact_pair in rapier_context.contacts_with(entity) {
-            let other_entity = if contact_pair.collider1() == entity {
-                contact_pair.collider2()
-            } else {
-                contact_pair.collider1()
-            };
+        let mut other_entities: Vec<Entity> = rapier_context
+            .contacts_with(entity)
+            .map(|contact_pair| {
+                if contact_pair.collider1() == entity {
+                    contact_pair.collider2()
+                } else {
+                    contact_pair.collider1()
+                }
+            })
+            .collect();
+        other_entities.sort_unstable();
+        other_entities.dedup();
+        if collider.continuous {
+            // The whole frame motion is swept once per collider, before the
+            // points are projected out of the colliders
+            for (other_collider, other_transform, _) in colliders_query.iter_many(&other_entities) {
+                let other_transform = other_transform.compute_transform();
+                cloth.solve_swept_collisions(|previous, current| {
+                    let intersection = other_collider.cast_ray_and_get_normal(
+                        other_transform.translation,
+                        other_transform.rotation,
+                        *previous,
+                        *current - *previous,
+                        1.0,
+                        true,
+                    )?;
+                    // Points starting inside the collider are handled by the projection
+                    (intersection.toi > 0.0)
+                        .then(|| intersection.point + intersection.normal * collider.offset)
+                });
+            }
+        }
+        for other_entity in other_entities {
             let Ok((other_collider, other_transform, other_velocity)) =
                 colliders_query.get_mut(other_entity)
             else {
@@ -43,8 +70,8 @@ pub fn handle_collisions(
             let vel = other_velocity.as_ref().map_or(0.0, |v| {
                 v.linvel.length_squared() * delta_time * delta_time * collider.velocity_coefficient
             });
+            let other_transform = other_transform.compute_transform();
             cloth.solve_collisions(|point| {
-                let other_transform = other_transform.compute_transform();
                 let projected_point = other_collider.project_point(
                     other_transform.translation,
                     other_transform.rotation,
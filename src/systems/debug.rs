@@ -0,0 +1,80 @@
+#![allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+use crate::{components::cloth::Cloth, debug::ClothDebugConfig};
+use bevy::prelude::*;
+
+pub fn draw_cloths(
+    mut gizmos: Gizmos,
+    config: Res<ClothDebugConfig>,
+    cloths: Query<&Cloth>,
+    targets: Query<&GlobalTransform>,
+) {
+    for cloth in &cloths {
+        let positions = &cloth.current_point_positions;
+        if config.sticks {
+            let sticks = cloth.sticks();
+            for (([a, b], rest_length), mode) in sticks
+                .ids()
+                .iter()
+                .zip(sticks.lengths())
+                .zip(sticks.modes())
+            {
+                let (start, end) = (Vec3::from(positions[*a]), Vec3::from(positions[*b]));
+                let color = config.stick_color(*mode, start.distance(end), *rest_length);
+                gizmos.line(start, end, color);
+            }
+        }
+        if config.anchors {
+            for (point, (anchor, _)) in &cloth.anchored_points {
+                let position = Vec3::from(positions[*point]);
+                gizmos.sphere(
+                    position,
+                    Quat::IDENTITY,
+                    0.1,
+                    ClothDebugConfig::ANCHOR_COLOR,
+                );
+                if let Some(target) = anchor.custom_target.and_then(|e| targets.get(e).ok()) {
+                    gizmos.line(
+                        position,
+                        target.translation(),
+                        ClothDebugConfig::ANCHOR_COLOR,
+                    );
+                }
+            }
+        }
+        if config.velocities {
+            for (position, previous) in positions.iter().zip(&cloth.previous_point_positions) {
+                gizmos.ray(
+                    Vec3::from(*position),
+                    Vec3::from(*position - *previous) * config.velocity_scale,
+                    ClothDebugConfig::VELOCITY_COLOR,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rapier_collisions")]
+pub fn draw_colliders(
+    mut gizmos: Gizmos,
+    config: Res<ClothDebugConfig>,
+    cloths: Query<
+        (
+            &bevy::render::primitives::Aabb,
+            &crate::components::collider::ClothCollider,
+            &GlobalTransform,
+        ),
+        With<Cloth>,
+    >,
+) {
+    if !config.collider {
+        return;
+    }
+    for (aabb, collider, transform) in &cloths {
+        let bounds = Transform::from_translation(aabb.center.into())
+            .with_scale(collider.half_extents(aabb) * 2.0);
+        gizmos.cuboid(
+            transform.mul_transform(bounds),
+            ClothDebugConfig::COLLIDER_COLOR,
+        );
+    }
+}
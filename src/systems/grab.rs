@@ -0,0 +1,111 @@
+#![allow(
+    clippy::needless_pass_by_value,
+    clippy::type_complexity,
+    clippy::too_many_arguments
+)]
+use crate::{
+    components::cloth::Cloth,
+    grab::{pick_point, ClothGrab, ClothGrabConfig, ClothGrabTarget, GrabbedPoint},
+    vertex_anchor::VertexAnchor,
+};
+use bevy::{log, prelude::*, window::PrimaryWindow};
+
+pub fn grab(
+    mut commands: Commands,
+    config: Res<ClothGrabConfig>,
+    mut grab: ResMut<ClothGrab>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut cloths: Query<(Entity, &mut Cloth)>,
+    mut targets: Query<
+        (&mut Transform, &mut GlobalTransform),
+        (With<ClothGrabTarget>, Without<Camera>),
+    >,
+) {
+    let ray = windows
+        .get_single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .and_then(|cursor| {
+            cameras
+                .iter()
+                .filter(|(camera, _)| camera.is_active)
+                .find_map(|(camera, transform)| camera.viewport_to_world(transform, cursor))
+        });
+    if let Some(grabbed) = grab.grabbed {
+        if !buttons.pressed(config.button) {
+            release(&mut commands, &mut cloths, grabbed);
+            grab.grabbed = None;
+        } else if let Some((ray, Ok((mut transform, mut global_transform)))) =
+            ray.map(|ray| (ray, targets.get_mut(grabbed.target)))
+        {
+            transform.translation = ray.get_point(grabbed.distance);
+            *global_transform = GlobalTransform::from(*transform);
+        }
+        return;
+    }
+    if !buttons.just_pressed(config.button) {
+        return;
+    }
+    let Some(ray) = ray else {
+        return;
+    };
+    let Some((entity, mut cloth, point, distance)) = cloths
+        .iter_mut()
+        .filter_map(|(entity, cloth)| {
+            let (point, distance) = pick_point(&cloth, ray)?;
+            Some((entity, cloth, point, distance))
+        })
+        .min_by(|a, b| a.3.total_cmp(&b.3))
+    else {
+        return;
+    };
+    let previous_anchor = cloth.anchored_points.get(&point).copied();
+    let transform = Transform::from_translation(ray.get_point(distance));
+    let target = commands
+        .spawn((
+            TransformBundle::from_transform(transform),
+            ClothGrabTarget,
+            Name::new("Cloth grab target"),
+        ))
+        .id();
+    let anchor = VertexAnchor {
+        custom_target: Some(target),
+        ignore_vertex_position: true,
+        compliance: config.compliance,
+        ..Default::default()
+    };
+    cloth.pin_point(point, anchor, &GlobalTransform::from(transform));
+    log::debug!("Grabbed point {point} of cloth {entity:?}");
+    grab.grabbed = Some(GrabbedPoint {
+        cloth: entity,
+        point,
+        target,
+        distance,
+        previous_anchor,
+    });
+}
+
+fn release(
+    commands: &mut Commands,
+    cloths: &mut Query<(Entity, &mut Cloth)>,
+    grabbed: GrabbedPoint,
+) {
+    commands.entity(grabbed.target).despawn();
+    let Ok((_, mut cloth)) = cloths.get_mut(grabbed.cloth) else {
+        return;
+    };
+    // The point may have been anchored elsewhere in the meantime
+    let grab_anchor = cloth
+        .anchored_points
+        .get(&grabbed.point)
+        .is_some_and(|(anchor, _)| anchor.custom_target == Some(grabbed.target));
+    if grab_anchor {
+        cloth.unpin_point(grabbed.point);
+        // Soft anchored points return to their anchor
+        if let Some((anchor, local_position)) = grabbed.previous_anchor {
+            cloth.insert_anchor(grabbed.point, anchor, local_position);
+        }
+    }
+}
@@ -2,3 +2,8 @@
 pub mod cloth;
 #[cfg(feature = "rapier_collisions")]
 pub mod collisions;
+/// Cloth debug drawing systems
+#[cfg(feature = "gizmos")]
+pub mod debug;
+/// Cloth grabbing systems
+pub mod grab;
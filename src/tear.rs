@@ -0,0 +1,24 @@
+use crate::components::cloth::StickId;
+use bevy::reflect::Reflect;
+
+/// A cloth point duplicated when the cloth tears
+#[derive(Debug, Clone, Reflect)]
+pub struct PointSplit {
+    /// The original point id
+    pub original: usize,
+    /// The new point id, appended to the cloth points
+    pub new: usize,
+    /// The ids of the triangles now using the new point
+    pub triangles: Vec<usize>,
+}
+
+/// Result of a cloth stick tearing
+#[derive(Debug, Clone, Reflect)]
+pub struct ClothTear {
+    /// The torn stick
+    pub stick: StickId,
+    /// The ids of the triangles sharing the torn stick edge
+    pub triangles: Vec<usize>,
+    /// The points duplicated to split the cloth topology
+    pub splits: Vec<PointSplit>,
+}
@@ -0,0 +1,116 @@
+use crate::components::cloth::StickId;
+use bevy::reflect::Reflect;
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// Long range attachment constraint, limiting the distance between a cloth
+/// point and its nearest anchored point
+#[derive(Debug, Copy, Clone, Reflect)]
+pub struct Tether {
+    /// The constrained point id
+    pub point: usize,
+    /// The nearest anchored point id
+    pub anchor: usize,
+    /// The rest geodesic distance between the point and the anchor
+    pub rest_distance: f32,
+}
+
+/// Dijkstra queue entry, ordered by ascending distance
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Visit {
+    distance: f32,
+    point: usize,
+    anchor: usize,
+}
+
+impl Eq for Visit {}
+
+impl Ord for Visit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+impl PartialOrd for Visit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Tether {
+    /// Generates a tether for every point connected to an anchored point.
+    /// The rest distance is the geodesic distance along the cloth sticks,
+    /// using their target lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * `point_count` - the amount of cloth points
+    /// * `anchors` - the anchored point ids
+    /// * `sticks` - the cloth sticks and their target lengths
+    #[must_use]
+    pub fn generate(
+        point_count: usize,
+        anchors: impl Iterator<Item = usize>,
+        sticks: impl Iterator<Item = (StickId, f32)>,
+    ) -> Vec<Self> {
+        let mut neighbours: Vec<Vec<(usize, f32)>> = vec![Vec::new(); point_count];
+        for ([a, b], len) in sticks {
+            if a < point_count && b < point_count {
+                neighbours[a].push((b, len));
+                neighbours[b].push((a, len));
+            }
+        }
+        let mut nearest: Vec<Option<(usize, f32)>> = vec![None; point_count];
+        let mut queue: BinaryHeap<Visit> = anchors
+            .filter(|i| *i < point_count)
+            .map(|i| Visit {
+                distance: 0.0,
+                point: i,
+                anchor: i,
+            })
+            .collect();
+        while let Some(visit) = queue.pop() {
+            if nearest[visit.point].is_some() {
+                continue;
+            }
+            nearest[visit.point] = Some((visit.anchor, visit.distance));
+            for (neighbour, len) in &neighbours[visit.point] {
+                if nearest[*neighbour].is_none() {
+                    queue.push(Visit {
+                        distance: visit.distance + len,
+                        point: *neighbour,
+                        anchor: visit.anchor,
+                    });
+                }
+            }
+        }
+        nearest
+            .into_iter()
+            .enumerate()
+            .filter_map(|(point, nearest)| {
+                nearest
+                    .filter(|(anchor, _)| *anchor != point)
+                    .map(|(anchor, rest_distance)| Self {
+                        point,
+                        anchor,
+                        rest_distance,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geodesic_distances() {
+        let sticks = [([0, 1], 1.0), ([1, 2], 2.0), ([2, 3], 1.0)];
+        let tethers = Tether::generate(5, [0, 3].into_iter(), sticks.into_iter());
+        let distances: Vec<_> = tethers
+            .iter()
+            .map(|t| (t.point, t.anchor, t.rest_distance))
+            .collect();
+        assert_eq!(distances, vec![(1, 0, 1.0), (2, 3, 1.0)]);
+    }
+}
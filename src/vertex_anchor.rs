@@ -21,9 +21,47 @@ pub struct VertexAnchor {
     /// If [`Self::custom_offset`] is defined, it will then override the vertex
     /// position
     pub ignore_vertex_position: bool,
+    /// Optional anchor compliance, the inverse of its stiffness. A soft
+    /// anchored point is simulated and pulled toward its anchor through a
+    /// compliant constraint, lagging behind fast moving targets. Like
+    /// [`StickMode::Compliant`] sticks, the pull is consistent whatever the
+    /// frame rate. If not set, the point is rigidly pinned to the anchor
+    /// position
+    ///
+    /// [`StickMode::Compliant`]: crate::stick::StickMode::Compliant
+    pub compliance: Option<f32>,
+    /// Optional break threshold. The anchor is removed when the point is
+    /// pulled away from its anchor further than this distance: the distance
+    /// to the anchor position for a soft anchor, the total stretch of the
+    /// sticks connected to the point for a rigid anchor.
+    /// If not set, the anchor never breaks
+    pub break_threshold: Option<f32>,
+}
+
+/// A cloth point anchor change, see [`Cloth::pin_point`] and
+/// [`Cloth::unpin_point`]
+///
+/// [`Cloth::pin_point`]: crate::components::cloth::Cloth::pin_point
+/// [`Cloth::unpin_point`]: crate::components::cloth::Cloth::unpin_point
+#[derive(Debug, Copy, Clone, Reflect)]
+pub struct AnchorChange {
+    /// The cloth point id
+    pub point: usize,
+    /// The previous point anchor, `None` if the point was free
+    pub previous: Option<VertexAnchor>,
+    /// The new point anchor, `None` if the point was unpinned
+    pub anchor: Option<VertexAnchor>,
 }
 
 impl VertexAnchor {
+    /// Returns `true` if the anchor rigidly pins the point to the anchor
+    /// position, see [`Self::compliance`]
+    #[inline]
+    #[must_use]
+    pub const fn is_rigid(&self) -> bool {
+        self.compliance.is_none()
+    }
+
     /// Retrieves the anchor world space position.
     ///
     /// # Arguments
@@ -0,0 +1,141 @@
+use bevy::{log, math::Vec3A, reflect::Reflect, utils::HashMap};
+
+/// Volume preservation constraint for closed cloth meshes, applying an
+/// internal pressure keeping the enclosed volume close to a target volume
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct VolumeConstraint {
+    /// Triangles enclosing the volume
+    pub triangles: Vec<[usize; 3]>,
+    /// Initial enclosed volume
+    pub rest_volume: f32,
+    /// Target volume ratio of [`Self::rest_volume`]:
+    /// - `1.0` keeps the initial volume
+    /// - above `1.0` inflates the cloth
+    /// - below `1.0` deflates the cloth
+    pub target_ratio: f32,
+    /// Pressure coefficient, defining how strongly the target volume is
+    /// enforced, between 0.0 and 1.0
+    pub pressure: f32,
+    /// Volume gradient of every point, kept between solves
+    #[reflect(ignore)]
+    gradients: Vec<Vec3A>,
+}
+
+impl VolumeConstraint {
+    /// Creates a new volume constraint from mesh triangle `indices`, using
+    /// the current volume as rest volume.
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - the cloth point positions
+    /// * `indices` - the mesh indices
+    /// * `target_ratio` - Target volume ratio of the rest volume
+    /// * `pressure` - Pressure coefficient, between 0.0 and 1.0
+    #[must_use]
+    pub fn new(positions: &[Vec3A], indices: &[usize], target_ratio: f32, pressure: f32) -> Self {
+        let triangles: Vec<_> = indices
+            .chunks_exact(3)
+            .map(|c| [c[0], c[1], c[2]])
+            .collect();
+        let mut edges: HashMap<[usize; 2], u32> = HashMap::with_capacity(indices.len());
+        for [a, b, c] in &triangles {
+            for [x, y] in [[*a, *b], [*b, *c], [*c, *a]] {
+                *edges.entry([x.min(y), x.max(y)]).or_default() += 1;
+            }
+        }
+        if edges.values().any(|count| *count != 2) {
+            log::warn!("Cloth volume constraint is used on a mesh which is not closed");
+        }
+        let mut res = Self {
+            triangles,
+            rest_volume: 0.0,
+            target_ratio,
+            pressure,
+            gradients: Vec::new(),
+        };
+        res.rest_volume = res.compute_volume(positions);
+        res
+    }
+
+    /// Computes the signed volume enclosed by [`Self::triangles`]
+    #[must_use]
+    pub fn compute_volume(&self, positions: &[Vec3A]) -> f32 {
+        self.triangles
+            .iter()
+            .map(|[a, b, c]| positions[*a].cross(positions[*b]).dot(positions[*c]))
+            .sum::<f32>()
+            / 6.0
+    }
+
+    /// Retrieves the target volume, from the rest volume and target ratio
+    #[inline]
+    #[must_use]
+    pub fn target_volume(&self) -> f32 {
+        self.rest_volume * self.target_ratio
+    }
+
+    /// Moves the `positions` to match the target volume
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - the cloth point positions
+    /// * `weights` - the weight (inverse mass) of every point
+    /// * `stiffness` - the constraint stiffness for this iteration
+    pub fn solve(
+        &mut self,
+        positions: &mut [Vec3A],
+        weights: impl Fn(usize) -> f32,
+        stiffness: f32,
+    ) {
+        let constraint = self.compute_volume(positions) - self.target_volume();
+        if constraint.abs() <= f32::EPSILON {
+            return;
+        }
+        self.gradients.clear();
+        self.gradients.resize(positions.len(), Vec3A::ZERO);
+        for [a, b, c] in &self.triangles {
+            let [p_a, p_b, p_c] = [positions[*a], positions[*b], positions[*c]];
+            self.gradients[*a] += p_b.cross(p_c) / 6.0;
+            self.gradients[*b] += p_c.cross(p_a) / 6.0;
+            self.gradients[*c] += p_a.cross(p_b) / 6.0;
+        }
+        let sum: f32 = self
+            .gradients
+            .iter()
+            .enumerate()
+            .map(|(i, g)| weights(i) * g.length_squared())
+            .sum();
+        if sum <= f32::EPSILON {
+            return;
+        }
+        let scale = -stiffness * constraint / sum;
+        for (i, (position, gradient)) in positions.iter_mut().zip(&self.gradients).enumerate() {
+            *position += *gradient * weights(i) * scale;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TETRAHEDRON: [Vec3A; 4] = [Vec3A::ZERO, Vec3A::X, Vec3A::Y, Vec3A::Z];
+    const INDICES: [usize; 12] = [0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3];
+
+    #[test]
+    fn tetrahedron_volume() {
+        let constraint = VolumeConstraint::new(&TETRAHEDRON, &INDICES, 1.0, 1.0);
+        assert!((constraint.rest_volume - 1.0 / 6.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn inflates_to_target() {
+        let mut constraint = VolumeConstraint::new(&TETRAHEDRON, &INDICES, 2.0, 1.0);
+        let mut positions = TETRAHEDRON;
+        for _ in 0..10 {
+            constraint.solve(&mut positions, |_| 1.0, 1.0);
+        }
+        let volume = constraint.compute_volume(&positions);
+        assert!((volume - constraint.target_volume()).abs() < 1e-3);
+    }
+}